@@ -0,0 +1,507 @@
+use std::{
+    path::{Path, PathBuf},
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use common::{
+    bench::{format_micros, Baseline, Samples, Stats},
+    export::{self, ImageOptions, Palette},
+    geom::{Point, Rect},
+    inputs::{InputCache, InputSpec, YEAR},
+    render::{Canvas, RenderOptions},
+    Answers, Error, Part, Solution, Solver, Verdict,
+};
+use day06::Replay;
+
+mod replay;
+
+#[derive(Debug, Parser)]
+#[command(about = "Advent of Code 2024 runner")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Solve the puzzle of one day
+    Run {
+        day: u8,
+        /// Only solve this part, both parts are solved by default
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+        /// Puzzle input: `example`, `exampleN` or `testdata` of the day, a path or `-` for stdin
+        #[arg(long, default_value = "testdata")]
+        input: String,
+    },
+    /// Draw the map of a day with the cells that make up the answer highlighted (days 4, 6 and 8)
+    Show {
+        day: u8,
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2), default_value_t = 1)]
+        part: u8,
+        /// Puzzle input: `example`, `exampleN` or `testdata` of the day, a path or `-` for stdin
+        #[arg(long, default_value = "testdata")]
+        input: String,
+        #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+        color: ColorChoice,
+        /// Don't explain the colours below the map
+        #[arg(long)]
+        no_legend: bool,
+        /// Only draw these rows, e.g. `20..60`
+        #[arg(long, value_parser = parse_range)]
+        rows: Option<(i64, i64)>,
+        /// Only draw these columns, e.g. `0..80`
+        #[arg(long, value_parser = parse_range)]
+        cols: Option<(i64, i64)>,
+    },
+    /// Save the map that `show` draws as SVG or PNG image
+    Export {
+        day: u8,
+        /// Image file, the format follows from the extension `.svg` or `.png`
+        out: PathBuf,
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2), default_value_t = 1)]
+        part: u8,
+        /// Puzzle input: `example`, `exampleN` or `testdata` of the day, a path or `-` for stdin
+        #[arg(long, default_value = "testdata")]
+        input: String,
+        /// `light`, `dark` or `#rrggbb` colours for background, unmarked cells, text and marks, comma separated
+        #[arg(long, default_value = "light")]
+        palette: Palette,
+        /// Pixels per cell
+        #[arg(long, default_value_t = 8)]
+        cell_size: u32,
+        /// Don't explain the colours below the map (SVG only)
+        #[arg(long)]
+        no_legend: bool,
+    },
+    /// Replay the day 6 guard's walk step by step in the terminal, or save it as animation
+    Replay {
+        /// Puzzle input: `example`, `exampleN` or `testdata` of the day, a path or `-` for stdin
+        #[arg(long, default_value = "testdata")]
+        input: String,
+        /// Steps per second
+        #[arg(long, default_value_t = 20.0)]
+        speed: f64,
+        /// Put an extra obstacle at `row,col`, as in part 2
+        #[arg(long, value_parser = parse_point)]
+        obstacle: Option<Point>,
+        /// Save the replay as animated `.gif` or asciinema `.cast` instead of playing it
+        #[arg(long)]
+        out: Option<PathBuf>,
+        /// `light`, `dark` or `#rrggbb` colours for background, unmarked cells, text and marks, comma separated (GIF only)
+        #[arg(long, default_value = "light")]
+        palette: Palette,
+        /// Pixels per cell (GIF only)
+        #[arg(long, default_value_t = 8)]
+        cell_size: u32,
+        #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+        color: ColorChoice,
+        /// Only draw these rows, e.g. `20..60`
+        #[arg(long, value_parser = parse_range)]
+        rows: Option<(i64, i64)>,
+        /// Only draw these columns, e.g. `0..80`
+        #[arg(long, value_parser = parse_range)]
+        cols: Option<(i64, i64)>,
+    },
+    /// Solve the day 7 equations with other operators and print how each one works out
+    Calibrate {
+        /// Operators to try, comma separated, out of `+ * || - / ** ^ min max ||2 ||8 ||16`
+        #[arg(long, default_value = "+,*,||")]
+        ops: String,
+        /// Puzzle input: `example`, `exampleN` or `testdata` of the day, a path or `-` for stdin
+        #[arg(long, default_value = "testdata")]
+        input: String,
+        /// Print every way to solve an equation, not only the first one found
+        #[arg(long, conflicts_with = "semantics")]
+        all: bool,
+        /// How to evaluate the operators, out of `left-to-right`, `precedence` and `parenthesised`.
+        /// With more than one, each equation is solved with the first that works.
+        #[arg(long, default_value = "left-to-right")]
+        semantics: String,
+    },
+    /// Solve every day and compare the answers with the known ones
+    Verify {
+        /// File with the known answers, defaults to the workspace's answers.txt
+        #[arg(long)]
+        answers: Option<PathBuf>,
+    },
+    /// Time parsing and both parts of every day over many runs
+    Bench {
+        /// Only benchmark this day, all days by default
+        day: Option<u8>,
+        /// Runs per day
+        #[arg(long, default_value_t = 20)]
+        iterations: usize,
+        /// Stop running a day after this many seconds, even if not all iterations are done
+        #[arg(long, default_value_t = 10.0)]
+        budget_secs: f64,
+        /// Save the results as JSON baseline
+        #[arg(long)]
+        save: Option<PathBuf>,
+        /// Compare the results with a JSON baseline and fail on regressions
+        #[arg(long)]
+        compare: Option<PathBuf>,
+        /// Slowdown of the median in percent that counts as regression
+        #[arg(long, default_value_t = 10.0)]
+        threshold: f64,
+    },
+    /// Download puzzle inputs into the input cache (see AOC_SESSION and AOC_CACHE_DIR)
+    Fetch {
+        /// Only fetch this day, all days by default
+        day: Option<u8>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum ColorChoice {
+    /// Colours if stdout is a terminal and NO_COLOR isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+fn parse_range(s: &str) -> Result<(i64, i64), String> {
+    let (start, end) = s.split_once("..").ok_or_else(|| format!("expected a range like 10..20, found '{}'", s))?;
+    let start = if start.is_empty() { 0 } else { start.parse().map_err(|e| format!("{}: '{}'", e, start))? };
+    let end = if end.is_empty() { i64::MAX } else { end.parse().map_err(|e| format!("{}: '{}'", e, end))? };
+    Ok((start, end))
+}
+
+fn parse_point(s: &str) -> Result<Point, String> {
+    let (row, col) = s.split_once(',').ok_or_else(|| format!("expected row,col, found '{}'", s))?;
+    let coordinate = |c: &str| c.trim().parse::<i64>().map_err(|e| format!("{}: '{}'", e, c));
+    Ok(Point::new(coordinate(row)?, coordinate(col)?))
+}
+
+fn solvers() -> Vec<Box<dyn Solver>> {
+    vec![
+        Box::new(day01::Day01),
+        Box::new(day02::Day02),
+        Box::new(day03::Day03),
+        Box::new(day04::Day04),
+        Box::new(day05::Day05),
+        Box::new(day06::Day06),
+        Box::new(day07::Day07),
+        Box::new(day08::Day08),
+    ]
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = match cli.command {
+        Command::Run { day, part, input } => run(day, part, input),
+        Command::Show {
+            day,
+            part,
+            input,
+            color,
+            no_legend,
+            rows,
+            cols,
+        } => show(day, part, input, color, !no_legend, rows, cols),
+        Command::Export {
+            day,
+            out,
+            part,
+            input,
+            palette,
+            cell_size,
+            no_legend,
+        } => export(
+            day,
+            part,
+            input,
+            &out,
+            ImageOptions {
+                palette,
+                cell_size,
+                legend: !no_legend,
+            },
+        ),
+        Command::Replay {
+            input,
+            speed,
+            obstacle,
+            out,
+            palette,
+            cell_size,
+            color,
+            rows,
+            cols,
+        } => replay(
+            input,
+            speed,
+            obstacle,
+            out.as_deref(),
+            ImageOptions {
+                palette,
+                cell_size,
+                legend: false,
+            },
+            render_options(color, false, rows, cols),
+        ),
+        Command::Calibrate { ops, input, all, semantics } => calibrate(&ops, input, all, &semantics),
+        Command::Verify { answers } => verify(answers.as_deref()),
+        Command::Bench {
+            day,
+            iterations,
+            budget_secs,
+            save,
+            compare,
+            threshold,
+        } => bench(day, iterations, Duration::from_secs_f64(budget_secs), save.as_deref(), compare.as_deref(), threshold),
+        Command::Fetch { day } => fetch(day),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn solver(day: u8) -> Result<Box<dyn Solver>, Error> {
+    solvers()
+        .into_iter()
+        .find(|solver| solver.day() == day)
+        .ok_or_else(|| Error::GenericError(format!("No solver for day {}", day)))
+}
+
+fn run(day: u8, part: Option<u8>, input: String) -> Result<(), Error> {
+    let solver = solver(day)?;
+    let input = solver.load_input(&InputSpec::from(input.as_str()), InputCache::from_env().as_ref())?;
+
+    let parts: Vec<Part> = Part::ALL.into_iter().filter(|p| part.is_none() || part == Some(p.number())).collect();
+    let run = solver.run(&input.data, &parts).map_err(|e| e.in_input(&input.name))?;
+    println!("Day {:02} Parse: ({:?})", run.day, run.parse_elapsed);
+    for part_run in run.parts {
+        println!("Day {:02} {}: {} ({:?})", run.day, part_run.part, part_run.answer, part_run.elapsed);
+    }
+    Ok(())
+}
+
+fn canvas(day: u8, part: u8, input: String) -> Result<Canvas, Error> {
+    let solver = solver(day)?;
+    let input = solver.load_input(&InputSpec::from(input.as_str()), InputCache::from_env().as_ref())?;
+    let part = if part == 1 { Part::One } else { Part::Two };
+    solver
+        .canvas(&input.data, part)
+        .map_err(|e| e.in_input(&input.name))?
+        .ok_or_else(|| Error::GenericError(format!("Day {} has no map to show", day)))
+}
+
+fn show(day: u8, part: u8, input: String, color: ColorChoice, legend: bool, rows: Option<(i64, i64)>, cols: Option<(i64, i64)>) -> Result<(), Error> {
+    let canvas = canvas(day, part, input)?;
+    print!("{}", canvas.render(&render_options(color, legend, rows, cols)));
+    Ok(())
+}
+
+fn render_options(color: ColorChoice, legend: bool, rows: Option<(i64, i64)>, cols: Option<(i64, i64)>) -> RenderOptions {
+    let options = match color {
+        ColorChoice::Auto => RenderOptions::detect(),
+        ColorChoice::Always => RenderOptions::default().with_color(true),
+        ColorChoice::Never => RenderOptions::default().with_color(false),
+    };
+    let viewport = (rows.is_some() || cols.is_some()).then(|| {
+        let (min_row, max_row) = rows.unwrap_or((0, i64::MAX));
+        let (min_col, max_col) = cols.unwrap_or((0, i64::MAX));
+        Rect::new(Point::new(min_row, min_col), Point::new(max_row, max_col))
+    });
+    options.with_legend(legend).with_viewport(viewport)
+}
+
+fn replay(input: String, speed: f64, obstacle: Option<Point>, out: Option<&Path>, image: ImageOptions, render: RenderOptions) -> Result<(), Error> {
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(Error::GenericError(format!("Speed has to be positive, not {}", speed)));
+    }
+    let input = day06::Day06.load_input(&InputSpec::from(input.as_str()), InputCache::from_env().as_ref())?;
+    let grid = day06::Day06.parse(&input.data).map_err(|e| e.in_input(&input.name))?;
+    let replay = Replay::record(&grid, obstacle)?;
+    match out {
+        Some(path) => {
+            replay::export(&replay, path, speed, image, render)?;
+            println!("Replay of {} steps saved to {}", replay.steps.len() - 1, path.display());
+            Ok(())
+        }
+        None => replay::play(&replay, speed, render),
+    }
+}
+
+fn calibrate(ops: &str, input: String, all: bool, semantics: &str) -> Result<(), Error> {
+    let operators = day07::parse_operators(ops).map_err(|e| e.in_input("--ops"))?;
+    let semantics = day07::parse_semantics(semantics).map_err(|e| e.in_input("--semantics"))?;
+    let input = day07::Day07.load_input(&InputSpec::from(input.as_str()), InputCache::from_env().as_ref())?;
+    let equations = day07::Day07.parse(&input.data).map_err(|e| e.in_input(&input.name))?;
+    let (solved, total) = calibrate_equations(&equations.small, &operators, all, &semantics);
+    #[cfg(feature = "bigint")]
+    let (solved, total) = {
+        let (big_solved, big_total) = calibrate_equations(&equations.big, &operators, all, &semantics);
+        (solved + big_solved, total + big_total)
+    };
+    let names: Vec<String> = operators.iter().map(|operator| operator.to_string()).collect();
+    println!("{} of {} equations solved with {}, total calibration result {}", solved, equations.len(), names.join(" "), total);
+    Ok(())
+}
+
+/// Prints how each of `equations` is solved, returns how many are and the sum of their results.
+fn calibrate_equations<N: day07::Number>(
+    equations: &[day07::Equation<N>],
+    operators: &[day07::Operator],
+    all: bool,
+    semantics: &[day07::Semantics],
+) -> (usize, day07::Total)
+where
+    day07::Total: From<N>,
+{
+    let (mut solved, mut total) = (0, day07::Total::default());
+    for equation in equations.iter() {
+        let expressions: Vec<String> = if all {
+            equation.solutions(operators).iter().map(|solution| equation.expression(solution)).collect()
+        } else {
+            equation
+                .solved_by(operators, semantics)
+                .map(|solution| match semantics[..] {
+                    [_] => solution.expression,
+                    _ => format!("{}   ({})", solution.expression, solution.semantics),
+                })
+                .into_iter()
+                .collect()
+        };
+        if !expressions.is_empty() {
+            solved += 1;
+            total += day07::Total::from(equation.expected_result().clone());
+        }
+        for expression in expressions {
+            println!("{}", expression);
+        }
+    }
+    (solved, total)
+}
+
+fn export(day: u8, part: u8, input: String, out: &Path, options: ImageOptions) -> Result<(), Error> {
+    let canvas = canvas(day, part, input)?;
+    export::save(&canvas, &options, out)?;
+    println!("Day {:02} Part {} saved to {}", day, part, out.display());
+    Ok(())
+}
+
+fn verify(answers: Option<&Path>) -> Result<(), Error> {
+    let answers = match answers {
+        Some(path) => Answers::load(path)?,
+        None => known_answers()?,
+    };
+    let cache = InputCache::from_env();
+    let mut failures = 0;
+    println!("{:<4} {:<4} {:<20} {:<20} {:<8} {:>12}", "Day", "Part", "Answer", "Expected", "Status", "Time");
+    for solver in solvers() {
+        let day = solver.day();
+        let input = solver.load_input(&InputSpec::Named("testdata".to_string()), cache.as_ref());
+        let run = match input.and_then(|input| solver.run(&input.data, &Part::ALL).map_err(|e| e.in_input(&input.name))) {
+            Ok(run) => run,
+            Err(e) => {
+                failures += 1;
+                println!("{:<4} {:<4} {}", format!("{:02}", day), "-", e);
+                continue;
+            }
+        };
+        for part_run in run.parts {
+            let verdict = answers.check(day, part_run.part, &part_run.answer);
+            let expected = match &verdict {
+                Verdict::Pass => part_run.answer.clone(),
+                Verdict::Fail { expected } => expected.clone(),
+                Verdict::Unknown => "?".to_string(),
+            };
+            if let Verdict::Fail { .. } = verdict {
+                failures += 1;
+            }
+            println!(
+                "{:<4} {:<4} {:<20} {:<20} {:<8} {:>12}",
+                format!("{:02}", day),
+                part_run.part.number(),
+                part_run.answer,
+                expected,
+                verdict.to_string(),
+                format!("{:?}", part_run.elapsed)
+            );
+        }
+    }
+
+    if failures > 0 {
+        return Err(Error::GenericError(format!("{} answer(s) failed verification", failures)));
+    }
+    Ok(())
+}
+
+fn bench(day: Option<u8>, iterations: usize, budget: Duration, save: Option<&Path>, compare: Option<&Path>, threshold: f64) -> Result<(), Error> {
+    let baseline = compare.map(Baseline::load).transpose()?;
+    let cache = InputCache::from_env();
+    let mut all_stats: Vec<Stats> = Vec::new();
+    println!("{:<4} {:<6} {:>7} {:>12} {:>12} {:>12}", "Day", "Stage", "Runs", "Min", "Median", "p95");
+    for solver in solvers().iter().filter(|solver| day.is_none() || day == Some(solver.day())) {
+        let input = solver.load_input(&InputSpec::Named("testdata".to_string()), cache.as_ref())?;
+        let mut samples = Samples::default();
+        let now = Instant::now();
+        for _ in 0..iterations.max(1) {
+            samples.add(&solver.run(&input.data, &Part::ALL).map_err(|e| e.in_input(&input.name))?);
+            if now.elapsed() > budget {
+                break;
+            }
+        }
+        for stats in samples.stats(solver.day()) {
+            println!(
+                "{:<4} {:<6} {:>7} {:>12} {:>12} {:>12}",
+                format!("{:02}", stats.day),
+                stats.stage.to_string(),
+                stats.samples,
+                format_micros(stats.min_us),
+                format_micros(stats.median_us),
+                format_micros(stats.p95_us)
+            );
+            all_stats.push(stats);
+        }
+    }
+
+    let regressions = baseline.map(|baseline| baseline.regressions(&all_stats, threshold)).unwrap_or_default();
+    for regression in regressions.iter() {
+        println!(
+            "Regression: day {:02} {} median {} -> {} (+{:.1}%)",
+            regression.day,
+            regression.stage,
+            format_micros(regression.baseline_us),
+            format_micros(regression.current_us),
+            regression.slowdown_percent()
+        );
+    }
+    if let Some(path) = save {
+        Baseline { stats: all_stats }.save(path)?;
+        println!("Baseline saved to {}", path.display());
+    }
+    if !regressions.is_empty() {
+        return Err(Error::GenericError(format!("{} stage(s) slower than the baseline by more than {}%", regressions.len(), threshold)));
+    }
+    Ok(())
+}
+
+fn fetch(day: Option<u8>) -> Result<(), Error> {
+    let cache = InputCache::from_env().ok_or_else(|| Error::GenericError("No input cache directory, set AOC_CACHE_DIR".to_string()))?;
+    for solver in solvers().iter().filter(|solver| day.is_none() || day == Some(solver.day())) {
+        if cache.contains(YEAR, solver.day()) {
+            println!("Day {:02}: cached at {}", solver.day(), cache.path(YEAR, solver.day()).display());
+            continue;
+        }
+        cache.get(YEAR, solver.day())?;
+        println!("Day {:02}: fetched to {}", solver.day(), cache.path(YEAR, solver.day()).display());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "embed")]
+fn known_answers() -> Result<Answers, Error> {
+    include_str!("../../answers.txt").parse()
+}
+
+#[cfg(not(feature = "embed"))]
+fn known_answers() -> Result<Answers, Error> {
+    Answers::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../answers.txt"))
+}
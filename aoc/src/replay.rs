@@ -0,0 +1,135 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, IsTerminal, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use common::{
+    export::{GifWriter, ImageOptions},
+    render::{CastWriter, RenderOptions},
+    Error,
+};
+use crossterm::{
+    cursor,
+    event::{self, Event as TermEvent, KeyCode, KeyEventKind, KeyModifiers},
+    execute, terminal,
+};
+use day06::Replay;
+
+/// Saves the replay as animated GIF or asciinema cast, depending on the extension of `path`.
+pub fn export(replay: &Replay, path: &Path, speed: f64, image: ImageOptions, render: RenderOptions) -> Result<(), Error> {
+    let frame_time = Duration::from_secs_f64(1.0 / speed);
+    let io_error = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let out = BufWriter::new(File::create(path).map_err(io_error)?);
+    let mut player = replay.player();
+    match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()).as_deref() {
+        Some("gif") => {
+            let mut gif = GifWriter::new(out, player.canvas(), image, frame_time)?;
+            while let Some(changed) = player.step() {
+                gif.frame(player.canvas(), &changed)?;
+            }
+            gif.finish()?.flush().map_err(io_error)
+        }
+        Some("cast") => {
+            let mut cast = CastWriter::new(out, player.canvas(), render.with_color(true)).map_err(io_error)?;
+            while let Some(changed) = player.step() {
+                cast.frame(frame_time * player.steps_done() as u32, player.canvas(), &changed).map_err(io_error)?;
+            }
+            cast.finish().map_err(io_error).map(|_| ())
+        }
+        _ => Err(Error::GenericError(format!("Cannot export {}: the file name has to end in .gif or .cast", path.display()))),
+    }
+}
+
+/// Restores the terminal when playback ends, also on errors.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Plays the replay in the terminal: space pauses, `n` or → steps while paused, `+`/`-` change
+/// the speed and `q` quits.
+pub fn play(replay: &Replay, mut speed: f64, options: RenderOptions) -> Result<(), Error> {
+    if !io::stdout().is_terminal() {
+        return Err(Error::GenericError("Replay needs a terminal, use --out to save it as .gif or .cast".to_string()));
+    }
+    let terminal_error = |e: io::Error| Error::GenericError(format!("Terminal: {}", e));
+    let options = options.with_legend(false);
+    let mut player = replay.player();
+    let frame = player.canvas().render(&options);
+    let status_row = frame.lines().count() as u16 + 1;
+
+    let _raw = RawTerminal::enter().map_err(terminal_error)?;
+    let mut stdout = io::stdout();
+    write!(stdout, "{}", frame.replace('\n', "\r\n")).map_err(terminal_error)?;
+
+    let mut paused = false;
+    let mut next_step = Instant::now();
+    loop {
+        let status = match (player.is_done(), replay.cycle()) {
+            (true, Some(cycle)) => format!("the guard walks in a loop of {} steps from step {}", cycle.length, cycle.start),
+            (true, _) => "the guard left the map".to_string(),
+            (false, _) if paused => "paused".to_string(),
+            (false, _) => format!("{:.0} steps/s", speed),
+        };
+        write!(
+            stdout,
+            "\x1b[{};1H\x1b[2KStep {}/{}, {}   [space] pause  [n] step  [+/-] speed  [q] quit",
+            status_row,
+            player.steps_done(),
+            player.steps_total(),
+            status
+        )
+        .and_then(|_| stdout.flush())
+        .map_err(terminal_error)?;
+
+        let mut step = false;
+        let timeout = if paused || player.is_done() {
+            Duration::from_secs(3600)
+        } else {
+            next_step.saturating_duration_since(Instant::now())
+        };
+        if event::poll(timeout).map_err(terminal_error)? {
+            if let TermEvent::Key(key) = event::read().map_err(terminal_error)? {
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => break,
+                    KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
+                    KeyCode::Char(' ') => paused = !paused,
+                    KeyCode::Char('n') | KeyCode::Right => step = paused,
+                    KeyCode::Char('+') => speed = (speed * 2.0).min(10_000.0),
+                    KeyCode::Char('-') => speed = (speed / 2.0).max(0.25),
+                    _ => {}
+                }
+            }
+        } else if !paused {
+            step = true;
+            next_step = Instant::now() + Duration::from_secs_f64(1.0 / speed);
+        }
+
+        if step {
+            if let Some(changed) = player.step() {
+                write!(stdout, "{}", player.canvas().render_updates(&changed, &options)).map_err(terminal_error)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use std::{collections::HashMap, fmt::Display, path::Path, str::FromStr};
+
+use crate::{parse_token, read_test_data, Error, Part};
+
+/// Known answers for the puzzle inputs, keyed by day and part.
+///
+/// The answers file has one answer per line in the form `day part answer`, blank lines and
+/// lines starting with `#` are ignored:
+/// ```text
+/// # day part answer
+/// 4 1 2534
+/// 4 2 1866
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    answers: HashMap<(u8, u8), String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { .. } => write!(f, "FAIL"),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        read_test_data(path)?.parse().map_err(|e: Error| e.in_input(&path.display().to_string()))
+    }
+
+    pub fn get(&self, day: u8, part: Part) -> Option<&str> {
+        self.answers.get(&(day, part.number())).map(|answer| answer.as_str())
+    }
+
+    pub fn check(&self, day: u8, part: Part, answer: &str) -> Verdict {
+        match self.get(day, part) {
+            Some(expected) if expected == answer => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+impl FromStr for Answers {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (day, part, answer) = parse_answer(line).map_err(|e| e.at_line(i + 1))?;
+            answers.answers.insert((day, part), answer.to_string());
+        }
+        Ok(answers)
+    }
+}
+
+fn parse_answer(line: &str) -> Result<(u8, u8, &str), Error> {
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() != 3 {
+        return Err(Error::syntax(line, line, "expected 'day part answer'"));
+    }
+    let day: u8 = parse_token(line, parts[0])?;
+    let part: u8 = parse_token(line, parts[1])?;
+    if part != 1 && part != 2 {
+        return Err(Error::syntax(line, parts[1], "part must be 1 or 2"));
+    }
+    Ok((day, part, parts[2]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() {
+        let answers = Answers::from_str("# day part answer\n\n4 1 2534\n04 2 1866\n").unwrap();
+        assert_eq!(answers.get(4, Part::One), Some("2534"));
+        assert_eq!(answers.get(4, Part::Two), Some("1866"));
+        assert_eq!(answers.get(5, Part::One), None);
+
+        assert!(Answers::from_str("4 1").is_err());
+        let err = Answers::from_str("4 1 2534\n4 3 12").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:3: part must be 1 or 2 ('3')");
+        assert!(Answers::from_str("x 1 12").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::from_str("4 1 2534").unwrap();
+        assert_eq!(answers.check(4, Part::One, "2534"), Verdict::Pass);
+        assert_eq!(
+            answers.check(4, Part::One, "2535"),
+            Verdict::Fail {
+                expected: "2534".to_string()
+            }
+        );
+        assert_eq!(answers.check(4, Part::Two, "1866"), Verdict::Unknown);
+    }
+}
@@ -0,0 +1,226 @@
+use std::{fmt::Display, fs, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{read_test_data, Error, Part, Run};
+
+/// The timed stages of a solution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part1 => write!(f, "part1"),
+            Stage::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+impl From<Part> for Stage {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Stage::Part1,
+            Part::Two => Stage::Part2,
+        }
+    }
+}
+
+/// Summary of the durations measured for one stage of one day, in microseconds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub day: u8,
+    pub stage: Stage,
+    pub samples: usize,
+    pub min_us: f64,
+    pub median_us: f64,
+    pub p95_us: f64,
+}
+
+impl Stats {
+    pub fn from_samples(day: u8, stage: Stage, samples: &[Duration]) -> Self {
+        let mut us: Vec<f64> = samples.iter().map(|d| d.as_secs_f64() * 1e6).collect();
+        us.sort_by(|a, b| a.total_cmp(b));
+        Self {
+            day,
+            stage,
+            samples: us.len(),
+            min_us: us.first().copied().unwrap_or_default(),
+            median_us: percentile(&us, 50.0),
+            p95_us: percentile(&us, 95.0),
+        }
+    }
+}
+
+/// Nearest rank percentile of sorted values.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Collects the durations of repeated runs of one day.
+#[derive(Debug, Default)]
+pub struct Samples {
+    parse: Vec<Duration>,
+    part1: Vec<Duration>,
+    part2: Vec<Duration>,
+}
+
+impl Samples {
+    pub fn add(&mut self, run: &Run) {
+        self.parse.push(run.parse_elapsed);
+        for part_run in run.parts.iter() {
+            match part_run.part {
+                Part::One => self.part1.push(part_run.elapsed),
+                Part::Two => self.part2.push(part_run.elapsed),
+            }
+        }
+    }
+
+    pub fn stats(&self, day: u8) -> Vec<Stats> {
+        [(Stage::Parse, &self.parse), (Stage::Part1, &self.part1), (Stage::Part2, &self.part2)]
+            .into_iter()
+            .filter(|(_, samples)| !samples.is_empty())
+            .map(|(stage, samples)| Stats::from_samples(day, stage, samples))
+            .collect()
+    }
+}
+
+/// Benchmark results that later runs are compared against, stored as JSON.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    pub stats: Vec<Stats>,
+}
+
+/// A stage whose median got slower than the baseline by more than the threshold.
+#[derive(Debug, PartialEq)]
+pub struct Regression {
+    pub day: u8,
+    pub stage: Stage,
+    pub baseline_us: f64,
+    pub current_us: f64,
+}
+
+impl Regression {
+    pub fn slowdown_percent(&self) -> f64 {
+        (self.current_us / self.baseline_us - 1.0) * 100.0
+    }
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let json = read_test_data(path)?;
+        serde_json::from_str(&json).map_err(|e| Error::GenericError(format!("Invalid benchmark baseline {}: {}", path.display(), e)))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| Error::GenericError(e.to_string()))?;
+        fs::write(path, json + "\n").map_err(|source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn get(&self, day: u8, stage: Stage) -> Option<&Stats> {
+        self.stats.iter().find(|stats| stats.day == day && stats.stage == stage)
+    }
+
+    /// Stages of `current` with a median more than `threshold_percent` above the baseline median.
+    /// Stages that aren't in the baseline are skipped.
+    pub fn regressions(&self, current: &[Stats], threshold_percent: f64) -> Vec<Regression> {
+        current
+            .iter()
+            .filter_map(|stats| {
+                let baseline = self.get(stats.day, stats.stage)?;
+                (stats.median_us > baseline.median_us * (1.0 + threshold_percent / 100.0)).then_some(Regression {
+                    day: stats.day,
+                    stage: stats.stage,
+                    baseline_us: baseline.median_us,
+                    current_us: stats.median_us,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Duration given in microseconds in a readable unit, e.g. `812.3 µs` or `12.41 ms`.
+pub fn format_micros(us: f64) -> String {
+    if us < 1_000.0 {
+        format!("{:.1} µs", us)
+    } else if us < 1_000_000.0 {
+        format!("{:.2} ms", us / 1_000.0)
+    } else {
+        format!("{:.2} s", us / 1_000_000.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_micros).collect();
+        let stats = Stats::from_samples(4, Stage::Part1, &samples);
+        assert_eq!(stats.samples, 20);
+        assert_eq!(stats.min_us, 1.0);
+        assert_eq!(stats.median_us, 10.0);
+        assert_eq!(stats.p95_us, 19.0);
+
+        let stats = Stats::from_samples(4, Stage::Parse, &[Duration::from_millis(3)]);
+        assert_eq!((stats.min_us, stats.median_us, stats.p95_us), (3000.0, 3000.0, 3000.0));
+    }
+
+    #[test]
+    fn test_regressions() {
+        let stats = |day, stage, median_us| Stats {
+            day,
+            stage,
+            samples: 10,
+            min_us: median_us,
+            median_us,
+            p95_us: median_us,
+        };
+        let baseline = Baseline {
+            stats: vec![stats(4, Stage::Part1, 100.0), stats(4, Stage::Part2, 100.0)],
+        };
+        let current = vec![stats(4, Stage::Part1, 109.0), stats(4, Stage::Part2, 120.0), stats(5, Stage::Part1, 1e6)];
+        let regressions = baseline.regressions(&current, 10.0);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].stage, Stage::Part2);
+        assert!((regressions[0].slowdown_percent() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_baseline_roundtrip() {
+        let path = std::env::temp_dir().join(format!("aoc-baseline-{}.json", std::process::id()));
+        let mut samples = Samples::default();
+        samples.add(&Run {
+            day: 1,
+            parse_elapsed: Duration::from_micros(5),
+            parts: vec![],
+        });
+        let baseline = Baseline { stats: samples.stats(1) };
+        baseline.save(&path).unwrap();
+        let loaded = Baseline::load(&path).unwrap();
+        assert_eq!(loaded.stats, baseline.stats);
+        assert_eq!(loaded.get(1, Stage::Parse).unwrap().median_us, 5.0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_format_micros() {
+        assert_eq!(format_micros(812.34), "812.3 µs");
+        assert_eq!(format_micros(12_412.0), "12.41 ms");
+        assert_eq!(format_micros(9_250_000.0), "9.25 s");
+    }
+}
@@ -1,31 +1,169 @@
-use std::{fmt::Display, io, num::ParseIntError};
+use std::{fmt::Display, io, path::PathBuf, str::FromStr};
+
+type Cause = Box<dyn std::error::Error + Send + Sync>;
 
 #[derive(Debug)]
 pub enum Error {
+    /// Reading an input or data file failed.
+    Io { path: PathBuf, source: io::Error },
+    /// The input doesn't have the expected format.
+    Syntax(SyntaxError),
+    /// Downloading a puzzle input failed.
+    Fetch { url: String, message: String },
     GenericError(String),
-    SyntaxError(String),
+}
+
+/// A malformed piece of input together with where it was found.
+///
+/// Line parsers only know the line they are looking at, so they fill in the column and the
+/// offending text. The line number and the name of the input are added by the callers that
+/// know them, see [`Error::at_line`] and [`Error::in_input`].
+#[derive(Debug)]
+pub struct SyntaxError {
+    /// Name of the input, usually its path.
+    pub input: Option<String>,
+    /// 1 based line number, 0 if unknown.
+    pub line: usize,
+    /// 1 based column in characters, 0 if unknown.
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+    pub cause: Option<Cause>,
+}
+
+impl Error {
+    /// Syntax error for `token` found in `line`. The column is derived from the position of `token`
+    /// if it is a slice of `line`.
+    pub fn syntax(line: &str, token: &str, message: impl Into<String>) -> Self {
+        Error::Syntax(SyntaxError {
+            input: None,
+            line: 0,
+            column: column_of(line, token),
+            text: token.to_string(),
+            message: message.into(),
+            cause: None,
+        })
+    }
+
+    /// Set the line number of a syntax error, if it isn't known yet.
+    pub fn at_line(mut self, line: usize) -> Self {
+        if let Error::Syntax(ref mut e) = self {
+            if e.line == 0 {
+                e.line = line;
+            }
+        }
+        self
+    }
+
+    /// Set the name of the input a syntax error was found in, if it isn't known yet.
+    pub fn in_input(mut self, input: &str) -> Self {
+        if let Error::Syntax(ref mut e) = self {
+            e.input.get_or_insert_with(|| input.to_string());
+        }
+        self
+    }
+
+    fn with_cause(mut self, cause: Cause) -> Self {
+        if let Error::Syntax(ref mut e) = self {
+            e.cause = Some(cause);
+        }
+        self
+    }
+}
+
+/// Parse `token`, a slice of `line`, into a `T`. Failures become a [`SyntaxError`] pointing at the token.
+pub fn parse_token<T>(line: &str, token: &str) -> Result<T, Error>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    token.trim().parse::<T>().map_err(|e| {
+        let message = format!("cannot parse '{}': {}", token.trim(), e);
+        Error::syntax(line, token, message).with_cause(Box::new(e))
+    })
+}
+
+/// 1 based column of `token` in `line`, or 0 if `token` isn't a slice of `line`.
+fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+    if offset <= line.len() && line.is_char_boundary(offset) {
+        line[..offset].chars().count() + 1
+    } else {
+        0
+    }
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "Error: {}", self)
+        match self {
+            Error::Io { path, source } => write!(f, "Cannot read {}: {}", path.display(), source),
+            Error::Syntax(e) => write!(f, "{}", e),
+            Error::Fetch { url, message } => write!(f, "Cannot fetch {}: {}", url, message),
+            Error::GenericError(msg) => write!(f, "Error: {}", msg),
+        }
     }
 }
 
-impl From<io::Error> for Error {
-    fn from(v: io::Error) -> Self {
-        Error::GenericError(v.to_string())
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.input.as_deref().unwrap_or("<input>"))?;
+        if self.line > 0 {
+            write!(f, ":{}", self.line)?;
+            if self.column > 0 {
+                write!(f, ":{}", self.column)?;
+            }
+        }
+        write!(f, ": {}", self.message)?;
+        if !self.message.contains(&self.text) {
+            write!(f, " ('{}')", self.text)?;
+        }
+        Ok(())
     }
 }
 
-impl From<ParseIntError> for Error {
-    fn from(v: ParseIntError) -> Self {
-        Error::GenericError(v.to_string())
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { source, .. } => Some(source),
+            Error::Syntax(e) => e.cause.as_deref().map(|cause| cause as &(dyn std::error::Error + 'static)),
+            Error::Fetch { .. } | Error::GenericError(_) => None,
+        }
     }
 }
 
-impl From<strum::ParseError> for Error {
-    fn from(v: strum::ParseError) -> Self {
-        Error::GenericError(v.to_string())
+#[cfg(test)]
+mod tests {
+    use std::error::Error as _;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_token() {
+        let line = "3267: 81 4x0 27";
+        let token = line.split(" ").nth(2).unwrap();
+        let err = parse_token::<i64>(line, token).unwrap_err().at_line(2).in_input("day07/example.dat");
+        let Error::Syntax(ref syntax) = err else { panic!("expected a syntax error") };
+        assert_eq!(syntax.line, 2);
+        assert_eq!(syntax.column, 10);
+        assert_eq!(syntax.text, "4x0");
+        assert!(err.source().is_some());
+        assert_eq!(err.to_string(), "day07/example.dat:2:10: cannot parse '4x0': invalid digit found in string");
+    }
+
+    #[test]
+    fn test_at_line_keeps_first_line() {
+        let line = "1|x";
+        let err = Error::syntax(line, &line[2..], "not a page number").at_line(4).at_line(9);
+        assert_eq!(err.to_string(), "<input>:4:3: not a page number ('x')");
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn test_column_of_foreign_token() {
+        let line = "abc";
+        assert_eq!(column_of(line, &String::from("b")), 0);
+        assert_eq!(column_of(line, &line[1..]), 2);
+        assert_eq!(column_of(line, &line[3..]), 4);
     }
 }
@@ -0,0 +1,504 @@
+use std::{
+    fmt::Display,
+    fs,
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    geom::Point,
+    grid::Grid,
+    render::{Canvas, Color},
+    Error,
+};
+
+/// A colour in an exported image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl FromStr for Rgb {
+    type Err = Error;
+
+    /// Parses `#rrggbb`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let hex = s.trim().strip_prefix('#').filter(|hex| hex.len() == 6 && hex.is_ascii());
+        let channel = |i: usize| hex.and_then(|hex| u8::from_str_radix(&hex[i..i + 2], 16).ok());
+        match (channel(0), channel(2), channel(4)) {
+            (Some(r), Some(g), Some(b)) => Ok(Rgb(r, g, b)),
+            _ => Err(Error::syntax(s, s.trim(), format!("expected a colour like #ff8800, found '{}'", s.trim()))),
+        }
+    }
+}
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+}
+
+/// Colours of an exported image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    /// Empty cells: `.` on the map, or every unmarked cell of a canvas with a blank.
+    pub background: Rgb,
+    /// Unmarked cells that hold something, e.g. obstacles.
+    pub unmarked: Rgb,
+    /// Text of the legend in SVGs.
+    pub text: Rgb,
+    /// Replaces [`Color::PALETTE`] in order, the n-th colour is drawn as `marks[n % marks.len()]`.
+    pub marks: Vec<Rgb>,
+}
+
+impl Palette {
+    pub fn light() -> Self {
+        Self {
+            background: Rgb(0xff, 0xff, 0xff),
+            unmarked: Rgb(0x9e, 0x9e, 0x9e),
+            text: Rgb(0x21, 0x21, 0x21),
+            marks: [
+                "#d32f2f", "#388e3c", "#f9a825", "#1976d2", "#7b1fa2", "#0097a7", "#ff7043", "#9ccc65", "#ffca28", "#64b5f6", "#ba68c8", "#4dd0e1",
+            ]
+            .iter()
+            .map(|hex| hex.parse().expect("palette colours are valid"))
+            .collect(),
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            background: Rgb(0x0f, 0x0f, 0x23),
+            unmarked: Rgb(0x44, 0x44, 0x5a),
+            text: Rgb(0xcc, 0xcc, 0xcc),
+            marks: [
+                "#ff5555", "#50fa7b", "#f1fa8c", "#6272a4", "#ff79c6", "#8be9fd", "#ff9580", "#8aff80", "#ffff80", "#9580ff", "#ff80bf", "#80ffea",
+            ]
+            .iter()
+            .map(|hex| hex.parse().expect("palette colours are valid"))
+            .collect(),
+        }
+    }
+
+    pub fn rgb(&self, color: Color) -> Rgb {
+        let n = Color::PALETTE.iter().position(|c| *c == color).unwrap_or(0);
+        self.marks[n % self.marks.len()]
+    }
+
+    fn cell(&self, canvas: &Canvas, point: Point) -> Rgb {
+        match canvas.get_mark(point) {
+            Some(mark) => self.rgb(mark.color),
+            None if canvas.blank().is_some() || canvas.grid()[point] == '.' => self.background,
+            None => self.unmarked,
+        }
+    }
+}
+
+/// `light`, `dark`, or a comma separated list of `#rrggbb` colours: background, unmarked cells,
+/// text and one or more mark colours.
+impl FromStr for Palette {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "light" => Ok(Palette::light()),
+            "dark" => Ok(Palette::dark()),
+            _ => {
+                let colors = s.split(',').map(Rgb::from_str).collect::<Result<Vec<_>, _>>()?;
+                if colors.len() < 4 {
+                    return Err(Error::syntax(s, s, "expected light, dark or at least 4 colours: background, unmarked, text and marks"));
+                }
+                Ok(Palette {
+                    background: colors[0],
+                    unmarked: colors[1],
+                    text: colors[2],
+                    marks: colors[3..].to_vec(),
+                })
+            }
+        }
+    }
+}
+
+/// How a [`Canvas`] is turned into an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageOptions {
+    pub palette: Palette,
+    /// Width and height of the block of pixels drawn for each cell.
+    pub cell_size: u32,
+    /// Adds the canvas's legend below the grid. SVG only.
+    pub legend: bool,
+}
+
+impl Default for ImageOptions {
+    fn default() -> Self {
+        Self {
+            palette: Palette::light(),
+            cell_size: 8,
+            legend: true,
+        }
+    }
+}
+
+impl ImageOptions {
+    /// The cell size actually drawn, at least one pixel.
+    fn cell_size(&self) -> u32 {
+        self.cell_size.max(1)
+    }
+
+    fn line_width(&self) -> u32 {
+        (self.cell_size() / 4).max(1)
+    }
+
+    fn center(&self, point: Point) -> (i64, i64) {
+        let size = self.cell_size() as i64;
+        (point.col * size + size / 2, point.row * size + size / 2)
+    }
+
+    /// Width and height in pixels of the image of `grid`, or an error if it would have more than
+    /// `MAX_PIXELS` pixels.
+    fn dimensions(&self, grid: &Grid<char>) -> Result<(u32, u32), Error> {
+        let size = u64::from(self.cell_size());
+        let (width, height) = (grid.cols() as u64 * size, grid.rows() as u64 * size);
+        if width.saturating_mul(height) > MAX_PIXELS {
+            return Err(Error::GenericError(format!(
+                "{}x{} pixels are too many for an image, use a smaller cell size",
+                width, height
+            )));
+        }
+        Ok((width as u32, height as u32))
+    }
+}
+
+/// The largest image rendered into memory, 3 bytes per pixel.
+const MAX_PIXELS: u64 = 1 << 28;
+
+/// The canvas as SVG: a square per non empty cell, the polylines and the legend.
+pub fn to_svg(canvas: &Canvas, options: &ImageOptions) -> String {
+    let size = options.cell_size() as usize;
+    let grid = canvas.grid();
+    let (width, height) = (grid.cols() * size, grid.rows() * size);
+    let legend_line = 16;
+    let legend_height = if options.legend { canvas.legend().len() * legend_line } else { 0 };
+    let palette = &options.palette;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+        w = width,
+        h = height + legend_height
+    );
+    svg.push_str(&format!("<rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n", palette.background));
+    for ((row, col), _) in grid.iter() {
+        let fill = palette.cell(canvas, Point::from((row, col)));
+        if fill != palette.background {
+            svg.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{s}\" height=\"{s}\" fill=\"{}\"/>\n",
+                col * size,
+                row * size,
+                fill,
+                s = size
+            ));
+        }
+    }
+    for polyline in canvas.polylines() {
+        let points: Vec<String> = polyline
+            .points
+            .iter()
+            .map(|p| options.center(*p))
+            .map(|(x, y)| format!("{},{}", x, y))
+            .collect();
+        svg.push_str(&format!(
+            "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"round\"/>\n",
+            points.join(" "),
+            palette.rgb(polyline.color),
+            options.line_width()
+        ));
+    }
+    if options.legend {
+        for (i, (color, label)) in canvas.legend().iter().enumerate() {
+            let y = height + i * legend_line;
+            svg.push_str(&format!("<rect x=\"2\" y=\"{}\" width=\"12\" height=\"12\" fill=\"{}\"/>\n", y + 2, palette.rgb(*color)));
+            svg.push_str(&format!(
+                "<text x=\"20\" y=\"{}\" font-family=\"monospace\" font-size=\"12\" fill=\"{}\">{}</text>\n",
+                y + 12,
+                palette.text,
+                escape_xml(label)
+            ));
+        }
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The canvas as RGB pixels, `cell_size` pixels per cell, polylines drawn over the cells.
+pub fn to_pixels(canvas: &Canvas, options: &ImageOptions) -> Result<(u32, u32, Vec<u8>), Error> {
+    let size = options.cell_size();
+    let grid = canvas.grid();
+    let (width, height) = options.dimensions(grid)?;
+    let mut pixels = vec![0; width as usize * height as usize * 3];
+    let mut plot = |x: i64, y: i64, Rgb(r, g, b): Rgb| {
+        if (0..width as i64).contains(&x) && (0..height as i64).contains(&y) {
+            let i = (y as usize * width as usize + x as usize) * 3;
+            pixels[i..i + 3].copy_from_slice(&[r, g, b]);
+        }
+    };
+
+    for ((row, col), _) in grid.iter() {
+        let fill = options.palette.cell(canvas, Point::from((row, col)));
+        for y in 0..size {
+            for x in 0..size {
+                plot((col as u32 * size + x) as i64, (row as u32 * size + y) as i64, fill);
+            }
+        }
+    }
+    let half_width = options.line_width() as i64 / 2;
+    for polyline in canvas.polylines() {
+        let color = options.palette.rgb(polyline.color);
+        let mut dots = |x: i64, y: i64| {
+            for dy in -half_width..=half_width {
+                for dx in -half_width..=half_width {
+                    plot(x + dx, y + dy, color);
+                }
+            }
+        };
+        for segment in polyline.points.windows(2) {
+            line(options.center(segment[0]), options.center(segment[1]), &mut dots);
+        }
+        if let [point] = polyline.points[..] {
+            let (x, y) = options.center(point);
+            dots(x, y);
+        }
+    }
+    Ok((width, height, pixels))
+}
+
+pub fn to_png(canvas: &Canvas, options: &ImageOptions) -> Result<Vec<u8>, Error> {
+    let (width, height, pixels) = to_pixels(canvas, options)?;
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, width, height);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(|e| Error::GenericError(format!("Cannot encode PNG: {}", e)))?;
+    Ok(png)
+}
+
+/// Writes the canvas as SVG or PNG, depending on the extension of `path`.
+pub fn save(canvas: &Canvas, options: &ImageOptions, path: &Path) -> Result<(), Error> {
+    let data = match path.extension().and_then(|ext| ext.to_str()).map(|ext| ext.to_ascii_lowercase()).as_deref() {
+        Some("svg") => to_svg(canvas, options).into_bytes(),
+        Some("png") => to_png(canvas, options)?,
+        _ => return Err(Error::GenericError(format!("Cannot export {}: the file name has to end in .svg or .png", path.display()))),
+    };
+    let io_error = |source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    let file = fs::File::create(path).map_err(io_error)?;
+    BufWriter::new(file).write_all(&data).map_err(io_error)
+}
+
+/// Writes an animated GIF of a canvas changing over time. Frames after the first only hold the
+/// rectangle around the cells that changed, so long animations of big maps stay small.
+/// Polylines aren't drawn.
+pub struct GifWriter<W: Write> {
+    encoder: gif::Encoder<W>,
+    options: ImageOptions,
+    colors: Vec<Rgb>,
+    delay: u16,
+}
+
+impl<W: Write> GifWriter<W> {
+    /// Starts the animation with `first`, showing each frame for `frame_time`.
+    pub fn new(out: W, first: &Canvas, options: ImageOptions, frame_time: Duration) -> Result<Self, Error> {
+        let (width, height) = options.dimensions(first.grid())?;
+        if width > u16::MAX as u32 || height > u16::MAX as u32 {
+            return Err(Error::GenericError(format!("{}x{} pixels are too many for a GIF", width, height)));
+        }
+        let mut colors = vec![options.palette.background, options.palette.unmarked];
+        colors.extend(options.palette.marks.iter().take(256 - 2).copied());
+        let global_palette: Vec<u8> = colors.iter().flat_map(|Rgb(r, g, b)| [*r, *g, *b]).collect();
+        let encoder = gif::Encoder::new(out, width as u16, height as u16, &global_palette).map_err(gif_error)?;
+        let mut gif = Self {
+            encoder,
+            options,
+            colors,
+            delay: (frame_time.as_millis() / 10).clamp(2, u16::MAX as u128) as u16,
+        };
+        gif.encoder.set_repeat(gif::Repeat::Infinite).map_err(gif_error)?;
+        let all = first.grid().bounds();
+        gif.write(first, all.min, all.max)?;
+        Ok(gif)
+    }
+
+    /// Adds a frame redrawing the `changed` cells of `canvas`.
+    pub fn frame(&mut self, canvas: &Canvas, changed: &[Point]) -> Result<(), Error> {
+        let bounds = canvas.grid().bounds();
+        let changed: Vec<&Point> = changed.iter().filter(|point| bounds.contains(point)).collect();
+        let (Some(min_row), Some(max_row)) = (changed.iter().map(|p| p.row).min(), changed.iter().map(|p| p.row).max()) else {
+            return Ok(());
+        };
+        let (min_col, max_col) = (changed.iter().map(|p| p.col).min().unwrap_or(0), changed.iter().map(|p| p.col).max().unwrap_or(0));
+        self.write(canvas, Point::new(min_row, min_col), Point::new(max_row + 1, max_col + 1))
+    }
+
+    /// Draws the cells from `min` to `max` (exclusive) as one frame.
+    fn write(&mut self, canvas: &Canvas, min: Point, max: Point) -> Result<(), Error> {
+        let size = self.options.cell_size() as i64;
+        let (width, height) = ((max.col - min.col) * size, (max.row - min.row) * size);
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                let point = Point::new(min.row + y / size, min.col + x / size);
+                let rgb = self.options.palette.cell(canvas, point);
+                pixels.push(self.colors.iter().position(|c| *c == rgb).unwrap_or(0) as u8);
+            }
+        }
+        let frame = gif::Frame {
+            left: (min.col * size) as u16,
+            top: (min.row * size) as u16,
+            width: width as u16,
+            height: height as u16,
+            delay: self.delay,
+            dispose: gif::DisposalMethod::Keep,
+            buffer: pixels.into(),
+            ..Default::default()
+        };
+        self.encoder.write_frame(&frame).map_err(gif_error)
+    }
+
+    pub fn finish(self) -> Result<W, Error> {
+        self.encoder.into_inner().map_err(|e| Error::GenericError(format!("Cannot write GIF: {}", e)))
+    }
+}
+
+fn gif_error(e: gif::EncodingError) -> Error {
+    Error::GenericError(format!("Cannot encode GIF: {}", e))
+}
+
+/// Bresenham's line from `from` to `to`, both ends included.
+fn line((mut x, mut y): (i64, i64), (x1, y1): (i64, i64), plot: &mut impl FnMut(i64, i64)) {
+    let (dx, dy) = ((x1 - x).abs(), -(y1 - y).abs());
+    let (sx, sy) = ((x1 - x).signum(), (y1 - y).signum());
+    let mut err = dx + dy;
+    loop {
+        plot(x, y);
+        if x == x1 && y == y1 {
+            break;
+        }
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+    }
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn canvas() -> Canvas {
+        let mut canvas = Canvas::new(Grid::from_str("..#\n...\n").unwrap());
+        canvas.mark(Point::new(1, 0), Color::Green);
+        canvas.add_polyline([Point::new(0, 0), Point::new(1, 2)], Color::Red);
+        canvas.add_legend(Color::Green, "a < b");
+        canvas
+    }
+
+    #[test]
+    fn test_parse_palette() {
+        assert_eq!("#ff8000".parse::<Rgb>().unwrap(), Rgb(255, 128, 0));
+        assert_eq!(Rgb(1, 2, 255).to_string(), "#0102ff");
+        assert!("ff8000".parse::<Rgb>().is_err());
+        let palette: Palette = "#000000,#111111,#ffffff,#ff0000,#00ff00".parse().unwrap();
+        assert_eq!(palette.rgb(Color::Red), Rgb(255, 0, 0));
+        assert_eq!(palette.rgb(Color::Yellow), Rgb(255, 0, 0));
+        assert_eq!("dark".parse::<Palette>().unwrap(), Palette::dark());
+        assert!("#000000".parse::<Palette>().is_err());
+    }
+
+    #[test]
+    fn test_svg() {
+        let options = ImageOptions {
+            cell_size: 10,
+            ..Default::default()
+        };
+        let svg = to_svg(&canvas(), &options);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"30\" height=\"36\""));
+        assert!(svg.contains("<rect x=\"20\" y=\"0\" width=\"10\" height=\"10\" fill=\"#9e9e9e\"/>"));
+        assert!(svg.contains("<rect x=\"0\" y=\"10\" width=\"10\" height=\"10\" fill=\"#388e3c\"/>"));
+        assert!(svg.contains("<polyline points=\"5,5 25,15\""));
+        assert!(svg.contains(">a &lt; b</text>"));
+        assert_eq!(svg.matches("<rect").count(), 4);
+    }
+
+    #[test]
+    fn test_pixels() {
+        let options = ImageOptions {
+            cell_size: 4,
+            ..Default::default()
+        };
+        let (width, height, pixels) = to_pixels(&canvas(), &options).unwrap();
+        assert_eq!((width, height, pixels.len()), (12, 8, 12 * 8 * 3));
+        let at = |x: u32, y: u32| {
+            let i = ((y * width + x) * 3) as usize;
+            Rgb(pixels[i], pixels[i + 1], pixels[i + 2])
+        };
+        assert_eq!(at(11, 0), Rgb(0x9e, 0x9e, 0x9e));
+        assert_eq!(at(0, 7), Rgb(0x38, 0x8e, 0x3c));
+        assert_eq!(at(2, 2), Rgb(0xd3, 0x2f, 0x2f));
+        assert_eq!(at(10, 6), Rgb(0xd3, 0x2f, 0x2f));
+        assert_eq!(at(11, 7), Rgb(0xff, 0xff, 0xff));
+
+        let png = to_png(&canvas(), &options).unwrap();
+        assert_eq!(&png[1..4], b"PNG");
+
+        for cell_size in [600, 1000, u32::MAX] {
+            let options = ImageOptions {
+                cell_size,
+                ..Default::default()
+            };
+            let mut big = Canvas::new(Grid::new(130, 130, '.'));
+            big.mark(Point::new(0, 0), Color::Red);
+            assert!(to_pixels(&big, &options).unwrap_err().to_string().contains("too many"));
+            assert!(to_png(&big, &options).is_err());
+            assert!(GifWriter::new(Vec::new(), &big, options, Duration::from_millis(100)).is_err());
+        }
+        let options = ImageOptions {
+            cell_size: 0,
+            ..Default::default()
+        };
+        let (width, height, pixels) = to_pixels(&canvas(), &options).unwrap();
+        assert_eq!((width, height, pixels.len()), (3, 2, 3 * 2 * 3));
+    }
+
+    #[test]
+    fn test_gif() {
+        let mut canvas = canvas();
+        let mut gif = GifWriter::new(Vec::new(), &canvas, ImageOptions::default(), Duration::from_millis(100)).unwrap();
+        canvas.mark(Point::new(0, 1), Color::Blue);
+        gif.frame(&canvas, &[Point::new(0, 1)]).unwrap();
+        gif.frame(&canvas, &[]).unwrap();
+        let data = gif.finish().unwrap();
+        assert_eq!(&data[..6], b"GIF89a");
+
+        let mut decoder = gif::DecodeOptions::new().read_info(&data[..]).unwrap();
+        assert_eq!((decoder.width(), decoder.height()), (24, 16));
+        let first = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!((first.width, first.height, first.delay), (24, 16, 10));
+        let second = decoder.read_next_frame().unwrap().unwrap();
+        assert_eq!((second.left, second.top, second.width, second.height), (8, 0, 8, 8));
+        assert!(second.buffer.iter().all(|i| *i == 2 + 3));
+        assert!(decoder.read_next_frame().unwrap().is_none());
+    }
+}
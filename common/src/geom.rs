@@ -0,0 +1,274 @@
+use std::{
+    fmt::Display,
+    ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign},
+};
+
+mod dir;
+pub use dir::{Compass, Dir4, Dir8, DirSet};
+
+/// A position on a map. Rows grow downwards, columns to the right, and both may be negative,
+/// so stepping off a map never underflows.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub row: i64,
+    pub col: i64,
+}
+
+/// Difference between two points.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector {
+    pub rows: i64,
+    pub cols: i64,
+}
+
+/// Rectangle of points with `min <= point < max`, e.g. the cells of a grid.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Point {
+    pub const fn new(row: i64, col: i64) -> Self {
+        Self { row, col }
+    }
+
+    pub fn manhattan(&self, other: &Point) -> i64 {
+        (*other - *self).manhattan()
+    }
+
+    pub fn chebyshev(&self, other: &Point) -> i64 {
+        (*other - *self).chebyshev()
+    }
+
+    /// Smallest step from this point towards `other` that only passes through integer points.
+    pub fn direction_to(&self, other: &Point) -> Vector {
+        (*other - *self).reduced()
+    }
+
+    /// `(row, col)` index into a grid, if the point has no negative coordinate.
+    pub fn to_index(&self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.row).ok()?, usize::try_from(self.col).ok()?))
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Self {
+        Point::new(row as i64, col as i64)
+    }
+}
+
+impl Vector {
+    pub const fn new(rows: i64, cols: i64) -> Self {
+        Self { rows, cols }
+    }
+
+    pub fn manhattan(&self) -> i64 {
+        self.rows.abs() + self.cols.abs()
+    }
+
+    pub fn chebyshev(&self) -> i64 {
+        self.rows.abs().max(self.cols.abs())
+    }
+
+    /// The vector divided by the gcd of its components, e.g. `(4, -6)` becomes `(2, -3)`.
+    pub fn reduced(&self) -> Vector {
+        let d = gcd(self.rows, self.cols);
+        if d == 0 {
+            return *self;
+        }
+        Vector::new(self.rows / d, self.cols / d)
+    }
+
+    /// Rotated by 90° clockwise as seen on a map, so up turns right.
+    pub fn rotate_right(&self) -> Vector {
+        Vector::new(self.cols, -self.rows)
+    }
+
+    /// Rotated by 90° counter clockwise as seen on a map, so up turns left.
+    pub fn rotate_left(&self) -> Vector {
+        Vector::new(-self.cols, self.rows)
+    }
+}
+
+impl Rect {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// The cells of a `rows` x `cols` grid.
+    pub fn with_size(rows: usize, cols: usize) -> Self {
+        Self::new(Point::new(0, 0), Point::new(rows as i64, cols as i64))
+    }
+
+    pub fn rows(&self) -> i64 {
+        (self.max.row - self.min.row).max(0)
+    }
+
+    pub fn cols(&self) -> i64 {
+        (self.max.col - self.min.col).max(0)
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        (self.min.row..self.max.row).contains(&point.row) && (self.min.col..self.max.col).contains(&point.col)
+    }
+
+    /// All points of the rectangle, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (self.min.row..self.max.row).flat_map(move |row| (self.min.col..self.max.col).map(move |col| Point::new(row, col)))
+    }
+}
+
+/// Greatest common divisor, always non negative.
+pub fn gcd(a: i64, b: i64) -> i64 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, v: Vector) -> Self::Output {
+        Point::new(self.row + v.rows, self.col + v.cols)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) {
+        *self = *self + v;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, v: Vector) -> Self::Output {
+        Point::new(self.row - v.rows, self.col - v.cols)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) {
+        *self = *self - v;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, other: Point) -> Self::Output {
+        Vector::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Self::Output {
+        Vector::new(self.rows + other.rows, self.cols + other.cols)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Self::Output {
+        Vector::new(self.rows - other.rows, self.cols - other.cols)
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, factor: i64) -> Self::Output {
+        Vector::new(self.rows * factor, self.cols * factor)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Self::Output {
+        Vector::new(-self.rows, -self.cols)
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.row, self.col)
+    }
+}
+
+impl Display for Vector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rows: {}, cols: {}", self.rows, self.cols)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let a = Point::new(1, 8);
+        let b = Point::new(3, 7);
+        assert_eq!(b - a, Vector::new(2, -1));
+        assert_eq!(a + (b - a) * 2, Point::new(5, 6));
+        assert_eq!(a - (b - a), Point::new(-1, 9));
+        assert_eq!(-(b - a), a - b);
+        let mut p = a;
+        p += Vector::new(1, 1);
+        p -= Vector::new(0, 2);
+        assert_eq!(p, Point::new(2, 7));
+    }
+
+    #[test]
+    fn test_distances() {
+        let a = Point::new(1, 8);
+        let b = Point::new(-3, 5);
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(b.manhattan(&a), 7);
+    }
+
+    #[test]
+    fn test_reduced() {
+        assert_eq!(Vector::new(4, -6).reduced(), Vector::new(2, -3));
+        assert_eq!(Vector::new(0, -5).reduced(), Vector::new(0, -1));
+        assert_eq!(Vector::new(0, 0).reduced(), Vector::new(0, 0));
+        assert_eq!(Point::new(1, 1).direction_to(&Point::new(7, 4)), Vector::new(2, 1));
+        assert_eq!(gcd(-12, 18), 6);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let up = Vector::new(-1, 0);
+        let right = up.rotate_right();
+        assert_eq!(right, Vector::new(0, 1));
+        assert_eq!(right.rotate_right(), Vector::new(1, 0));
+        assert_eq!(up.rotate_left(), Vector::new(0, -1));
+        assert_eq!(Vector::new(2, 3).rotate_right().rotate_left(), Vector::new(2, 3));
+    }
+
+    #[test]
+    fn test_rect() {
+        let rect = Rect::with_size(2, 3);
+        assert!(rect.contains(&Point::new(1, 2)));
+        assert!(!rect.contains(&Point::new(2, 0)));
+        assert!(!rect.contains(&Point::new(0, -1)));
+        assert_eq!((rect.rows(), rect.cols()), (2, 3));
+        assert_eq!(rect.points().count(), 6);
+        assert_eq!(rect.points().last(), Some(Point::new(1, 2)));
+    }
+
+    #[test]
+    fn test_to_index() {
+        assert_eq!(Point::new(2, 3).to_index(), Some((2, 3)));
+        assert_eq!(Point::new(-1, 3).to_index(), None);
+        assert_eq!(Point::from((4, 5)), Point::new(4, 5));
+    }
+}
@@ -0,0 +1,346 @@
+use std::{fmt::Display, marker::PhantomData, str::FromStr};
+
+use super::Vector;
+use crate::Error;
+
+/// A set of compass directions, each one step on a map.
+pub trait Compass: Copy + Eq + 'static {
+    /// All directions, clockwise starting with up.
+    const ALL: &'static [Self];
+
+    /// Position in [`Compass::ALL`], also the direction's bit in a [`DirSet`].
+    fn index(self) -> usize;
+
+    fn delta(self) -> Vector;
+
+    /// All directions, clockwise starting with up.
+    fn all() -> impl Iterator<Item = Self> {
+        Self::ALL.iter().copied()
+    }
+
+    /// The direction `steps` positions further clockwise, counter clockwise for negative `steps`.
+    fn rotate(self, steps: i32) -> Self {
+        let n = Self::ALL.len() as i32;
+        Self::ALL[(self.index() as i32 + steps).rem_euclid(n) as usize]
+    }
+}
+
+/// The four directions a guard can walk in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir4 {
+    #[default]
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// The four directions plus the diagonals between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Dir8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Compass for Dir4 {
+    const ALL: &'static [Self] = &[Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn delta(self) -> Vector {
+        match self {
+            Dir4::Up => Vector::new(-1, 0),
+            Dir4::Right => Vector::new(0, 1),
+            Dir4::Down => Vector::new(1, 0),
+            Dir4::Left => Vector::new(0, -1),
+        }
+    }
+}
+
+impl Compass for Dir8 {
+    const ALL: &'static [Self] = &[
+        Dir8::Up,
+        Dir8::UpRight,
+        Dir8::Right,
+        Dir8::DownRight,
+        Dir8::Down,
+        Dir8::DownLeft,
+        Dir8::Left,
+        Dir8::UpLeft,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn delta(self) -> Vector {
+        match self {
+            Dir8::Up => Vector::new(-1, 0),
+            Dir8::UpRight => Vector::new(-1, 1),
+            Dir8::Right => Vector::new(0, 1),
+            Dir8::DownRight => Vector::new(1, 1),
+            Dir8::Down => Vector::new(1, 0),
+            Dir8::DownLeft => Vector::new(1, -1),
+            Dir8::Left => Vector::new(0, -1),
+            Dir8::UpLeft => Vector::new(-1, -1),
+        }
+    }
+}
+
+impl Dir4 {
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// `^`, `>`, `v` or `<`, as the guard is drawn on the map.
+    pub fn symbol(self) -> char {
+        ['^', '>', 'v', '<'][self.index()]
+    }
+
+    pub fn arrow(self) -> char {
+        ['↑', '→', '↓', '←'][self.index()]
+    }
+}
+
+impl Dir8 {
+    /// The next direction clockwise, 45° to the right.
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// The next direction counter clockwise, 45° to the left.
+    pub fn turn_left(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn arrow(self) -> char {
+        ['↑', '↗', '→', '↘', '↓', '↙', '←', '↖'][self.index()]
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        Dir8::ALL[dir.index() * 2]
+    }
+}
+
+impl TryFrom<char> for Dir4 {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Dir4::all()
+            .find(|dir| dir.symbol() == c || dir.arrow() == c)
+            .ok_or_else(|| Error::syntax("", "", format!("'{}' is not one of ^>v< or ↑→↓←", c)))
+    }
+}
+
+impl TryFrom<char> for Dir8 {
+    type Error = Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        if let Ok(dir) = Dir4::try_from(c) {
+            return Ok(dir.into());
+        }
+        Dir8::all()
+            .find(|dir| dir.arrow() == c)
+            .ok_or_else(|| Error::syntax("", "", format!("'{}' is not one of ^>v<, ↑↗→↘↓↙←↖", c)))
+    }
+}
+
+fn parse_single_char<T: TryFrom<char, Error = Error>>(s: &str) -> Result<T, Error> {
+    let mut chars = s.trim().chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => T::try_from(c).map_err(|_| Error::syntax(s, s.trim(), format!("unknown direction '{}'", s.trim()))),
+        _ => Err(Error::syntax(s, s.trim(), format!("expected a single direction character, found '{}'", s.trim()))),
+    }
+}
+
+impl FromStr for Dir4 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_single_char(s)
+    }
+}
+
+impl FromStr for Dir8 {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_single_char(s)
+    }
+}
+
+/// Displays as `^>v<`, or as an arrow with `{:#}`.
+impl Display for Dir4 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            write!(f, "{}", self.arrow())
+        } else {
+            write!(f, "{}", self.symbol())
+        }
+    }
+}
+
+impl Display for Dir8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.arrow())
+    }
+}
+
+/// Directions packed into the bits of a byte, e.g. the headings a cell was entered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DirSet<D> {
+    bits: u8,
+    dirs: PhantomData<D>,
+}
+
+impl<D: Compass> DirSet<D> {
+    pub const fn empty() -> Self {
+        Self::from_bits(0)
+    }
+
+    pub const fn from_bits(bits: u8) -> Self {
+        Self { bits, dirs: PhantomData }
+    }
+
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Adds `dir`, returns `false` if it was already in the set.
+    pub fn insert(&mut self, dir: D) -> bool {
+        let had = self.contains(dir);
+        self.bits |= 1 << dir.index();
+        !had
+    }
+
+    pub fn remove(&mut self, dir: D) -> bool {
+        let had = self.contains(dir);
+        self.bits &= !(1 << dir.index());
+        had
+    }
+
+    pub fn contains(&self, dir: D) -> bool {
+        self.bits & (1 << dir.index()) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.bits.count_ones() as usize
+    }
+
+    /// The directions in the set, clockwise starting with up.
+    pub fn iter(&self) -> impl Iterator<Item = D> + '_ {
+        D::all().filter(|dir| self.contains(*dir))
+    }
+}
+
+impl<D: Compass> Default for DirSet<D> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<D: Compass> FromIterator<D> for DirSet<D> {
+    fn from_iter<I: IntoIterator<Item = D>>(iter: I) -> Self {
+        let mut set = Self::empty();
+        for dir in iter {
+            set.insert(dir);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(Dir4::Up.turn_right(), Dir4::Right);
+        assert_eq!(Dir4::Left.turn_right(), Dir4::Up);
+        assert_eq!(Dir4::Up.turn_left(), Dir4::Left);
+        assert_eq!(Dir4::Down.opposite(), Dir4::Up);
+        assert_eq!(Dir8::UpLeft.turn_right(), Dir8::Up);
+        assert_eq!(Dir8::Up.turn_left(), Dir8::UpLeft);
+        assert_eq!(Dir8::UpRight.opposite(), Dir8::DownLeft);
+        for dir in Dir4::all() {
+            assert_eq!(dir.turn_right().delta(), dir.delta().rotate_right());
+            assert_eq!(dir.opposite().delta(), -dir.delta());
+        }
+        for dir in Dir8::all() {
+            assert_eq!(dir.opposite().delta(), -dir.delta());
+        }
+    }
+
+    #[test]
+    fn test_all() {
+        assert_eq!(Dir4::all().count(), 4);
+        assert_eq!(Dir8::all().filter(|dir| dir.is_diagonal()).count(), 4);
+        assert_eq!(Dir8::from(Dir4::Left), Dir8::Left);
+        for (i, dir) in Dir8::all().enumerate() {
+            assert_eq!(dir.index(), i);
+            assert_eq!(dir.delta().chebyshev(), 1);
+        }
+    }
+
+    #[test]
+    fn test_parse_display() {
+        for dir in Dir4::all() {
+            assert_eq!(dir.to_string().parse::<Dir4>().unwrap(), dir);
+            assert_eq!(format!("{:#}", dir).parse::<Dir4>().unwrap(), dir);
+        }
+        for dir in Dir8::all() {
+            assert_eq!(dir.to_string().parse::<Dir8>().unwrap(), dir);
+        }
+        assert_eq!(Dir4::Down.to_string(), "v");
+        assert_eq!(format!("{:#}", Dir4::Down), "↓");
+        assert_eq!("<".parse::<Dir8>().unwrap(), Dir8::Left);
+        assert_eq!(Dir4::try_from('↗').unwrap_err().to_string(), "<input>: '↗' is not one of ^>v< or ↑→↓←");
+        assert_eq!("^^".parse::<Dir4>().unwrap_err().to_string(), "<input>: expected a single direction character, found '^^'");
+    }
+
+    #[test]
+    fn test_dir_set() {
+        let mut set = DirSet::<Dir4>::empty();
+        assert!(set.is_empty());
+        assert!(set.insert(Dir4::Left));
+        assert!(set.insert(Dir4::Up));
+        assert!(!set.insert(Dir4::Up));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.bits(), 0b1001);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![Dir4::Up, Dir4::Left]);
+        assert!(set.remove(Dir4::Up));
+        assert!(!set.contains(Dir4::Up));
+        let all: DirSet<Dir8> = Dir8::all().collect();
+        assert_eq!(all.bits(), 0xff);
+    }
+}
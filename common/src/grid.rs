@@ -0,0 +1,317 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::{
+    geom::{Point, Rect},
+    Error,
+};
+
+/// Offsets of the 4 orthogonal neighbours, clockwise starting up.
+const NEIGHBOURS4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+/// Offsets of the 8 surrounding neighbours, clockwise starting up.
+const NEIGHBOURS8: [(isize, isize); 8] = [(-1, 0), (-1, 1), (0, 1), (1, 1), (1, 0), (1, -1), (0, -1), (-1, -1)];
+
+/// A rectangular grid of cells stored row by row in one buffer. Cells are addressed by `(row, col)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    cols: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(rows: usize, cols: usize, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            cells: vec![fill; rows * cols],
+            rows,
+            cols,
+        }
+    }
+
+    /// Build a grid from text, one row per line, mapping every character to a cell.
+    /// All lines must have the same length.
+    pub fn parse_with(data: &str, mut f: impl FnMut(char) -> T) -> Result<Self, Error> {
+        let mut cells = Vec::with_capacity(data.len());
+        let mut rows = 0;
+        let mut cols = 0;
+        for (i, line) in data.lines().enumerate() {
+            let len_before = cells.len();
+            cells.extend(line.chars().map(&mut f));
+            let row_len = cells.len() - len_before;
+            if rows > 0 && row_len != cols {
+                let message = format!("expected {} columns like the rows before, found {}", cols, row_len);
+                return Err(Error::syntax(line, line, message).at_line(i + 1));
+            }
+            cols = row_len;
+            rows += 1;
+        }
+        if cells.is_empty() {
+            return Err(Error::syntax(data, data, "the grid is empty"));
+        }
+        Ok(Self { cells, rows, cols })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn contains(&self, row: usize, col: usize) -> bool {
+        row < self.rows && col < self.cols
+    }
+
+    pub fn bounds(&self) -> Rect {
+        Rect::with_size(self.rows, self.cols)
+    }
+
+    /// Cell at `point`, `None` if the point is off the grid.
+    pub fn get_at(&self, point: Point) -> Option<&T> {
+        let (row, col) = point.to_index()?;
+        self.get(row, col)
+    }
+
+    pub fn get_at_mut(&mut self, point: Point) -> Option<&mut T> {
+        let (row, col) = point.to_index()?;
+        self.get_mut(row, col)
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<&T> {
+        self.contains(row, col).then(|| &self.cells[row * self.cols + col])
+    }
+
+    pub fn get_mut(&mut self, row: usize, col: usize) -> Option<&mut T> {
+        if self.contains(row, col) {
+            Some(&mut self.cells[row * self.cols + col])
+        } else {
+            None
+        }
+    }
+
+    /// Cell at `(row, col)` moved by `(d_row, d_col)`, if that is still on the grid.
+    pub fn offset(&self, row: usize, col: usize, d_row: isize, d_col: isize) -> Option<(usize, usize)> {
+        let row = row.checked_add_signed(d_row)?;
+        let col = col.checked_add_signed(d_col)?;
+        self.contains(row, col).then_some((row, col))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.cols..(row + 1) * self.cols]
+    }
+
+    /// The cells of column `col`, none for a grid without columns.
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.cols.max(1))
+    }
+
+    /// The rows as slices, none for a grid without columns.
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.cols.max(1))
+    }
+
+    pub fn iter_cols(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.cols).map(|col| self.col(col))
+    }
+
+    /// All cells with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.cells.iter().enumerate().map(|(i, cell)| ((i / self.cols, i % self.cols), cell))
+    }
+
+    /// Positions of the up to 4 orthogonal neighbours of `(row, col)` on the grid.
+    pub fn neighbours4(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS4.iter().filter_map(move |(d_row, d_col)| self.offset(row, col, *d_row, *d_col))
+    }
+
+    /// Positions of the up to 8 surrounding neighbours of `(row, col)` on the grid.
+    pub fn neighbours8(&self, row: usize, col: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS8.iter().filter_map(move |(d_row, d_col)| self.offset(row, col, *d_row, *d_col))
+    }
+
+    /// Position of the first cell, row by row, that matches `pred`.
+    pub fn find(&self, pred: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter().find(|(_, cell)| pred(cell)).map(|(pos, _)| pos)
+    }
+
+    pub fn find_all<'a>(&'a self, pred: impl Fn(&T) -> bool + 'a) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.iter().filter(move |(_, cell)| pred(cell)).map(|(pos, _)| pos)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// The grid turned 90° clockwise, the first column becomes the first row reversed.
+    pub fn rotate_right(&self) -> Self {
+        let cells = (0..self.cols)
+            .flat_map(|col| (0..self.rows).rev().map(move |row| (row, col)))
+            .map(|pos| self[pos].clone())
+            .collect();
+        Grid {
+            cells,
+            rows: self.cols,
+            cols: self.rows,
+        }
+    }
+
+    /// The grid mirrored left to right.
+    pub fn flip_horizontal(&self) -> Self {
+        Grid {
+            cells: self.iter_rows().flat_map(|row| row.iter().rev().cloned()).collect(),
+            rows: self.rows,
+            cols: self.cols,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        assert!(self.contains(row, col), "({}, {}) is outside the {}x{} grid", row, col, self.rows, self.cols);
+        &self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut Self::Output {
+        assert!(self.contains(row, col), "({}, {}) is outside the {}x{} grid", row, col, self.rows, self.cols);
+        &mut self.cells[row * self.cols + col]
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get_at(point)
+            .unwrap_or_else(|| panic!("{} is outside the {}x{} grid", point, self.rows, self.cols))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_at_mut(point).unwrap_or_else(|| panic!("{} is outside the {}x{} grid", point, rows, cols))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse_with(s, |c| c)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.iter_rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "ab#\n.#c\nd..\n";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        assert_eq!((grid.rows(), grid.cols()), (3, 3));
+        assert_eq!(grid[(1, 2)], 'c');
+        assert_eq!(grid.get(2, 0), Some(&'d'));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.to_string(), EXAMPLE);
+
+        let walls = Grid::parse_with(EXAMPLE, |c| c == '#').unwrap();
+        assert_eq!(walls.find_all(|wall| *wall).collect::<Vec<_>>(), vec![(0, 2), (1, 1)]);
+
+        let err = Grid::from_str("abc\nab\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: expected 3 columns like the rows before, found 2 ('ab')");
+        assert!(Grid::from_str("").is_err());
+    }
+
+    #[test]
+    fn test_rows_and_cols() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        assert_eq!(grid.row(1), &['.', '#', 'c']);
+        assert_eq!(grid.col(2).collect::<String>(), "#c.");
+        assert_eq!(grid.iter_rows().count(), 3);
+        assert_eq!(grid.iter_cols().map(|col| col.collect::<String>()).collect::<Vec<_>>(), vec!["a.d", "b#.", "#c."]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        assert_eq!(grid.neighbours4(0, 0).collect::<Vec<_>>(), vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours4(1, 1).count(), 4);
+        assert_eq!(grid.neighbours8(0, 0).collect::<Vec<_>>(), vec![(0, 1), (1, 1), (1, 0)]);
+        assert_eq!(grid.neighbours8(1, 1).count(), 8);
+        assert_eq!(grid.neighbours8(2, 2).count(), 3);
+    }
+
+    #[test]
+    fn test_find_and_update() {
+        let mut grid = Grid::from_str(EXAMPLE).unwrap();
+        assert_eq!(grid.find(|c| *c == '#'), Some((0, 2)));
+        assert_eq!(grid.find(|c| *c == 'x'), None);
+        *grid.get_mut(2, 2).unwrap() = 'x';
+        grid[(0, 0)] = 'y';
+        assert_eq!(grid.find(|c| *c == 'x'), Some((2, 2)));
+        assert!(grid.get_mut(0, 3).is_none());
+        assert_eq!(grid.map(|c| c.is_alphabetic() as u8).to_string(), "110\n001\n101\n");
+        assert_eq!(Grid::new(2, 2, 0).to_string(), "00\n00\n");
+    }
+
+    #[test]
+    fn test_transforms() {
+        let grid = Grid::from_str("ab\ncd\nef\n").unwrap();
+        assert_eq!(grid.rotate_right().to_string(), "eca\nfdb\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "ba\ndc\nfe\n");
+        assert_eq!(grid.rotate_right().rotate_right().rotate_right().rotate_right(), grid);
+    }
+
+    #[test]
+    fn test_points() {
+        let mut grid = Grid::from_str(EXAMPLE).unwrap();
+        assert_eq!(grid.get_at(Point::new(1, 2)), Some(&'c'));
+        assert_eq!(grid.get_at(Point::new(-1, 2)), None);
+        assert_eq!(grid.get_at(Point::new(1, 3)), None);
+        grid[Point::new(2, 1)] = 'e';
+        assert_eq!(grid[(2, 1)], 'e');
+        assert!(grid.bounds().contains(&Point::new(2, 2)));
+        assert!(!grid.bounds().contains(&Point::new(3, 2)));
+    }
+
+    #[test]
+    fn test_zero_columns() {
+        for grid in [Grid::new(3, 0, 'x'), Grid::default()] {
+            assert_eq!(grid.iter_rows().count(), 0);
+            assert_eq!(grid.col(0).count(), 0);
+            assert_eq!(grid.iter_cols().count(), 0);
+            assert_eq!(grid.to_string(), "");
+        }
+    }
+}
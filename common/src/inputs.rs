@@ -0,0 +1,179 @@
+use std::{
+    io::{self, ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{read_test_data, Error};
+
+mod cache;
+mod fetch;
+pub use cache::InputCache;
+pub use fetch::{Fetcher, HttpFetcher, RateLimiter};
+
+pub const YEAR: u16 = 2024;
+
+/// A puzzle input together with a name for error messages.
+#[derive(Debug, Clone)]
+pub struct Input {
+    pub name: String,
+    pub data: String,
+}
+
+/// The inputs that come with a day's crate: `example.dat`, `example2.dat`, ..., `testdata.dat`
+/// in the crate directory. Build it with [`day_inputs!`](crate::day_inputs) so the directory is
+/// the crate's manifest directory and the inputs are embedded when the crate's `embed` feature is on.
+#[derive(Debug, Clone, Copy)]
+pub struct DayInputs {
+    pub dir: &'static str,
+    pub embedded: &'static [(&'static str, &'static str)],
+}
+
+/// Which input to load. Parsed from the command line:
+/// `-` is stdin, `example`, `exampleN` and `testdata` name one of the day's inputs and
+/// everything else is a path.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSpec {
+    Stdin,
+    Named(String),
+    Path(PathBuf),
+}
+
+impl InputSpec {
+    pub fn is_input_name(s: &str) -> bool {
+        s == "testdata" || s.strip_prefix("example").is_some_and(|n| n.chars().all(|c| c.is_ascii_digit()))
+    }
+}
+
+impl From<&str> for InputSpec {
+    fn from(s: &str) -> Self {
+        if s == "-" {
+            InputSpec::Stdin
+        } else if InputSpec::is_input_name(s) {
+            InputSpec::Named(s.to_string())
+        } else {
+            InputSpec::Path(PathBuf::from(s))
+        }
+    }
+}
+
+impl DayInputs {
+    /// Path of a named input, e.g. `<crate dir>/example2.dat` for `example2`.
+    pub fn path(&self, name: &str) -> PathBuf {
+        Path::new(self.dir).join(format!("{}.dat", name))
+    }
+
+    /// Content of a named input. Embedded inputs win over the files in the crate directory.
+    pub fn read(&self, name: &str) -> Result<String, Error> {
+        if let Some((_, data)) = self.embedded.iter().find(|(embedded_name, _)| *embedded_name == name) {
+            return Ok(data.to_string());
+        }
+        read_test_data(&self.path(name))
+    }
+
+    pub fn load(&self, spec: &InputSpec) -> Result<Input, Error> {
+        match spec {
+            InputSpec::Stdin => {
+                let mut data = String::new();
+                io::stdin().read_to_string(&mut data).map_err(|source| Error::Io {
+                    path: PathBuf::from("-"),
+                    source,
+                })?;
+                Ok(Input {
+                    name: "<stdin>".to_string(),
+                    data,
+                })
+            }
+            InputSpec::Named(name) => Ok(Input {
+                name: self.path(name).display().to_string(),
+                data: self.read(name)?,
+            }),
+            InputSpec::Path(path) => Ok(Input {
+                name: path.display().to_string(),
+                data: read_test_data(path)?,
+            }),
+        }
+    }
+
+    /// Like [`DayInputs::load`], but a `testdata` input that isn't in the crate directory
+    /// is taken from the input cache, which fetches it if needed.
+    pub fn load_or_fetch(&self, day: u8, spec: &InputSpec, cache: Option<&InputCache>) -> Result<Input, Error> {
+        match (self.load(spec), spec, cache) {
+            (Err(Error::Io { source, .. }), InputSpec::Named(name), Some(cache)) if source.kind() == ErrorKind::NotFound && name == "testdata" => {
+                Ok(Input {
+                    name: cache.path(YEAR, day).display().to_string(),
+                    data: cache.get(YEAR, day)?,
+                })
+            }
+            (result, _, _) => result,
+        }
+    }
+}
+
+/// [`DayInputs`] of the crate the macro is used in, listing the names of the inputs to embed
+/// when the crate is built with its `embed` feature:
+/// ```ignore
+/// const INPUTS: DayInputs = common::day_inputs!("example", "testdata");
+/// ```
+#[macro_export]
+macro_rules! day_inputs {
+    ($($name:literal),* $(,)?) => {{
+        #[cfg(feature = "embed")]
+        const EMBEDDED: &[(&str, &str)] = &[$(($name, include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $name, ".dat")))),*];
+        #[cfg(not(feature = "embed"))]
+        const EMBEDDED: &[(&str, &str)] = &[];
+        $crate::inputs::DayInputs {
+            dir: env!("CARGO_MANIFEST_DIR"),
+            embedded: EMBEDDED,
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_input_spec() {
+        assert_eq!(InputSpec::from("-"), InputSpec::Stdin);
+        assert_eq!(InputSpec::from("example"), InputSpec::Named("example".to_string()));
+        assert_eq!(InputSpec::from("example2"), InputSpec::Named("example2".to_string()));
+        assert_eq!(InputSpec::from("testdata"), InputSpec::Named("testdata".to_string()));
+        assert_eq!(InputSpec::from("examples"), InputSpec::Path(PathBuf::from("examples")));
+        assert_eq!(InputSpec::from("./day04/example.dat"), InputSpec::Path(PathBuf::from("./day04/example.dat")));
+    }
+
+    #[test]
+    fn test_read() {
+        let inputs = DayInputs {
+            dir: env!("CARGO_MANIFEST_DIR"),
+            embedded: &[("example", "1 2 3")],
+        };
+        assert_eq!(inputs.read("example").unwrap(), "1 2 3");
+        assert!(inputs.path("testdata").ends_with("common/testdata.dat"));
+        assert!(matches!(inputs.read("testdata"), Err(Error::Io { .. })));
+
+        let input = inputs.load(&InputSpec::Path(PathBuf::from("Cargo.toml"))).unwrap();
+        assert_eq!(input.name, "Cargo.toml");
+        assert!(input.data.contains("name = \"common\""));
+    }
+
+    #[test]
+    fn test_load_or_fetch() {
+        let cache_dir = std::env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        std::fs::create_dir_all(cache_dir.join("2024")).unwrap();
+        std::fs::write(cache_dir.join("2024").join("day09.dat"), "cached").unwrap();
+        let cache = InputCache::new(&cache_dir);
+        let inputs = DayInputs {
+            dir: env!("CARGO_MANIFEST_DIR"),
+            embedded: &[("example", "1 2 3")],
+        };
+
+        let input = inputs.load_or_fetch(9, &InputSpec::Named("testdata".to_string()), Some(&cache)).unwrap();
+        assert_eq!(input.data, "cached");
+        let input = inputs.load_or_fetch(9, &InputSpec::Named("example".to_string()), Some(&cache)).unwrap();
+        assert_eq!(input.data, "1 2 3");
+        assert!(inputs.load_or_fetch(9, &InputSpec::Named("example2".to_string()), Some(&cache)).is_err());
+        assert!(inputs.load_or_fetch(9, &InputSpec::Named("testdata".to_string()), None).is_err());
+        std::fs::remove_dir_all(&cache_dir).unwrap();
+    }
+}
@@ -0,0 +1,152 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use super::fetch::{Fetcher, HttpFetcher, DEFAULT_URL};
+use crate::{read_test_data, Error};
+
+/// Directory of puzzle inputs, `<dir>/<year>/dayNN.dat`. Inputs that aren't cached yet are
+/// fetched once and stored, an input that is in the cache is never fetched again.
+pub struct InputCache {
+    dir: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl InputCache {
+    /// A cache that only reads what is already in `dir`.
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            fetcher: None,
+        }
+    }
+
+    pub fn with_fetcher(mut self, fetcher: Box<dyn Fetcher>) -> Self {
+        self.fetcher = Some(fetcher);
+        self
+    }
+
+    /// Configured from the environment:
+    /// - `AOC_CACHE_DIR`: cache directory, defaults to `$XDG_CACHE_HOME/aoc` or `$HOME/.cache/aoc`
+    /// - `AOC_SESSION`: session cookie, without it missing inputs are not fetched
+    /// - `AOC_URL`: puzzle site, defaults to https://adventofcode.com
+    pub fn from_env() -> Option<Self> {
+        let dir = match env::var_os("AOC_CACHE_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => match env::var_os("XDG_CACHE_HOME") {
+                Some(cache_home) => PathBuf::from(cache_home).join("aoc"),
+                None => PathBuf::from(env::var_os("HOME")?).join(".cache").join("aoc"),
+            },
+        };
+        let mut cache = Self::new(&dir);
+        if let Ok(session) = env::var("AOC_SESSION") {
+            let url = env::var("AOC_URL").unwrap_or_else(|_| DEFAULT_URL.to_string());
+            cache = cache.with_fetcher(Box::new(HttpFetcher::new(&url, &session)));
+        }
+        Some(cache)
+    }
+
+    pub fn path(&self, year: u16, day: u8) -> PathBuf {
+        self.dir.join(year.to_string()).join(format!("day{:02}.dat", day))
+    }
+
+    pub fn contains(&self, year: u16, day: u8) -> bool {
+        self.path(year, day).is_file()
+    }
+
+    pub fn get(&self, year: u16, day: u8) -> Result<String, Error> {
+        let path = self.path(year, day);
+        if path.is_file() {
+            return read_test_data(&path);
+        }
+        let Some(fetcher) = &self.fetcher else {
+            return Err(Error::GenericError(format!(
+                "Input of day {} is not in the cache at {} and AOC_SESSION is not set to fetch it",
+                day,
+                path.display()
+            )));
+        };
+        let data = fetcher.fetch(year, day)?;
+        self.store(&path, &data)?;
+        Ok(data)
+    }
+
+    /// Write via a temporary file, so an interrupted write never leaves a truncated input in the cache.
+    fn store(&self, path: &Path, data: &str) -> Result<(), Error> {
+        let io_error = |source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        };
+        fs::create_dir_all(path.parent().unwrap()).map_err(io_error)?;
+        let tmp = path.with_extension("dat.tmp");
+        fs::write(&tmp, data).map_err(io_error)?;
+        fs::rename(&tmp, path).map_err(io_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    struct CountingFetcher {
+        calls: Rc<Cell<usize>>,
+    }
+
+    impl Fetcher for CountingFetcher {
+        fn fetch(&self, year: u16, day: u8) -> Result<String, Error> {
+            self.calls.set(self.calls.get() + 1);
+            if day > 25 {
+                return Err(Error::Fetch {
+                    url: format!("/{}/day/{}/input", year, day),
+                    message: "HTTP 404 Not Found".to_string(),
+                });
+            }
+            Ok(format!("{} {}\n", year, day))
+        }
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_fetches_once() {
+        let dir = temp_dir("once");
+        let calls = Rc::new(Cell::new(0));
+        let cache = InputCache::new(&dir).with_fetcher(Box::new(CountingFetcher { calls: calls.clone() }));
+
+        assert!(!cache.contains(2024, 4));
+        assert_eq!(cache.get(2024, 4).unwrap(), "2024 4\n");
+        assert_eq!(cache.get(2024, 4).unwrap(), "2024 4\n");
+        assert_eq!(calls.get(), 1);
+        assert!(cache.contains(2024, 4));
+        assert!(dir.join("2024").join("day04.dat").is_file());
+
+        // A new cache on the same directory finds the stored input without a fetcher
+        assert_eq!(InputCache::new(&dir).get(2024, 4).unwrap(), "2024 4\n");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_failed_fetch_is_not_cached() {
+        let dir = temp_dir("failed");
+        let calls = Rc::new(Cell::new(0));
+        let cache = InputCache::new(&dir).with_fetcher(Box::new(CountingFetcher { calls: calls.clone() }));
+        assert!(cache.get(2024, 26).is_err());
+        assert!(cache.get(2024, 26).is_err());
+        assert_eq!(calls.get(), 2);
+        assert!(!cache.contains(2024, 26));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_miss_without_fetcher() {
+        let cache = InputCache::new(&temp_dir("nofetcher"));
+        assert!(cache.get(2024, 1).unwrap_err().to_string().contains("AOC_SESSION is not set"));
+    }
+}
@@ -0,0 +1,171 @@
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::Error;
+
+/// Source of puzzle inputs that aren't available locally.
+pub trait Fetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, Error>;
+}
+
+pub const DEFAULT_URL: &str = "https://adventofcode.com";
+/// Minimum time between two requests to the puzzle site.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+const USER_AGENT: &str = "github.com/hvogeler/aoc2024 input fetcher";
+
+/// Fetches `{base_url}/{year}/day/{day}/input` with the session cookie of a logged in user.
+/// Requests are spaced at least `min_interval` apart.
+pub struct HttpFetcher {
+    base_url: String,
+    session: String,
+    rate_limiter: RateLimiter,
+}
+
+impl HttpFetcher {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.trim().to_string(),
+            rate_limiter: RateLimiter::new(DEFAULT_MIN_INTERVAL),
+        }
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.rate_limiter = RateLimiter::new(min_interval);
+        self
+    }
+
+    pub fn url(&self, year: u16, day: u8) -> String {
+        format!("{}/{}/day/{}/input", self.base_url, year, day)
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch(&self, year: u16, day: u8) -> Result<String, Error> {
+        let url = self.url(year, day);
+        self.rate_limiter.wait();
+        let response = ureq::get(&url)
+            .set("Cookie", &format!("session={}", self.session))
+            .set("User-Agent", USER_AGENT)
+            .call()
+            .map_err(|e| Error::Fetch {
+                url: url.clone(),
+                message: match e {
+                    ureq::Error::Status(code, response) => format!("HTTP {} {}", code, response.status_text()),
+                    e => e.to_string(),
+                },
+            })?;
+        response.into_string().map_err(|e| Error::Fetch {
+            url,
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Blocks callers so that two calls to [`RateLimiter::wait`] return at least `min_interval` apart.
+#[derive(Debug)]
+pub struct RateLimiter {
+    min_interval: Duration,
+    last: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            last: Mutex::new(None),
+        }
+    }
+
+    pub fn wait(&self) {
+        let mut last = self.last.lock().unwrap();
+        if let Some(last) = *last {
+            let elapsed = last.elapsed();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        *last = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, Mutex},
+    };
+
+    use super::*;
+
+    /// Minimal stand in for the puzzle site. Serves `input of day N` for requests that carry the
+    /// session cookie `test` and records the requested paths.
+    fn mock_server(requests: usize) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let recorded = paths.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let path = request_line.split(' ').nth(1).unwrap_or_default().to_string();
+                let mut authorized = false;
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    if header.trim().is_empty() {
+                        break;
+                    }
+                    if header.to_lowercase().starts_with("cookie:") && header.contains("session=test") {
+                        authorized = true;
+                    }
+                }
+                recorded.lock().unwrap().push(path.clone());
+
+                let day = path.strip_prefix("/2024/day/").and_then(|p| p.strip_suffix("/input"));
+                let (status, body) = match (authorized, day) {
+                    (false, _) => ("400 Bad Request", "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n".to_string()),
+                    (true, Some(day)) => ("200 OK", format!("input of day {}\n", day)),
+                    (true, None) => ("404 Not Found", "404 Not Found\n".to_string()),
+                };
+                write!(stream, "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}", status, body.len(), body).unwrap();
+            }
+        });
+        (base_url, paths)
+    }
+
+    #[test]
+    fn test_fetch() {
+        let (base_url, paths) = mock_server(1);
+        let fetcher = HttpFetcher::new(&base_url, "test\n");
+        assert_eq!(fetcher.fetch(2024, 6).unwrap(), "input of day 6\n");
+        assert_eq!(*paths.lock().unwrap(), vec!["/2024/day/6/input"]);
+    }
+
+    #[test]
+    fn test_fetch_without_valid_session() {
+        let (base_url, _) = mock_server(1);
+        let fetcher = HttpFetcher::new(&base_url, "expired");
+        let err = fetcher.fetch(2024, 6).unwrap_err();
+        assert_eq!(err.to_string(), format!("Cannot fetch {}/2024/day/6/input: HTTP 400 Bad Request", base_url));
+    }
+
+    #[test]
+    fn test_rate_limit() {
+        let (base_url, paths) = mock_server(3);
+        let fetcher = HttpFetcher::new(&base_url, "test").with_min_interval(Duration::from_millis(100));
+        let now = Instant::now();
+        for day in 1..=3 {
+            fetcher.fetch(2024, day).unwrap();
+        }
+        assert!(now.elapsed() >= Duration::from_millis(200));
+        assert_eq!(paths.lock().unwrap().len(), 3);
+    }
+}
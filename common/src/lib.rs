@@ -1,9 +1,21 @@
 use std::{fs, path::Path};
 
+mod answers;
+pub mod bench;
 mod error;
-pub use error::Error;
+pub mod export;
+pub mod geom;
+pub mod grid;
+pub mod inputs;
+pub mod render;
+mod solver;
+pub use answers::{Answers, Verdict};
+pub use error::{parse_token, Error, SyntaxError};
+pub use solver::{Part, PartRun, Run, Solution, Solver};
 
 pub fn read_test_data(path: &Path) -> Result<String, Error> {
-    let data = fs::read_to_string(path)?;
-    Ok(data)
+    fs::read_to_string(path).map_err(|source| Error::Io {
+        path: path.to_path_buf(),
+        source,
+    })
 }
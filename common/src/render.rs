@@ -0,0 +1,452 @@
+use std::{
+    env,
+    fmt::Display,
+    io::{self, IsTerminal, Write},
+    time::Duration,
+};
+
+use crate::{
+    geom::{Point, Rect},
+    grid::Grid,
+};
+
+/// Foreground colours every ANSI terminal knows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+}
+
+impl Color {
+    pub const PALETTE: [Color; 12] = [
+        Color::Red,
+        Color::Green,
+        Color::Yellow,
+        Color::Blue,
+        Color::Magenta,
+        Color::Cyan,
+        Color::BrightRed,
+        Color::BrightGreen,
+        Color::BrightYellow,
+        Color::BrightBlue,
+        Color::BrightMagenta,
+        Color::BrightCyan,
+    ];
+
+    /// The `n`th colour of the palette, starting over after the last one.
+    pub fn nth(n: usize) -> Color {
+        Color::PALETTE[n % Color::PALETTE.len()]
+    }
+
+    fn ansi_code(&self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::BrightRed => 91,
+            Color::BrightGreen => 92,
+            Color::BrightYellow => 93,
+            Color::BrightBlue => 94,
+            Color::BrightMagenta => 95,
+            Color::BrightCyan => 96,
+        }
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+            Color::BrightRed => "bright red",
+            Color::BrightGreen => "bright green",
+            Color::BrightYellow => "bright yellow",
+            Color::BrightBlue => "bright blue",
+            Color::BrightMagenta => "bright magenta",
+            Color::BrightCyan => "bright cyan",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A highlighted cell: its colour and optionally a glyph drawn instead of the cell's character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mark {
+    pub color: Color,
+    pub glyph: Option<char>,
+}
+
+/// A path through the centres of cells, e.g. the route of a guard. Only images draw polylines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    pub color: Color,
+}
+
+/// How a [`Canvas`] is rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Use ANSI colours, unmarked cells are dimmed. Without colours unmarked cells are drawn
+    /// as the canvas's blank, see [`Canvas::with_blank`].
+    pub color: bool,
+    pub legend: bool,
+    /// Only draw the cells inside this rectangle.
+    pub viewport: Option<Rect>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            color: false,
+            legend: true,
+            viewport: None,
+        }
+    }
+}
+
+impl RenderOptions {
+    /// Colours if stdout is a terminal and `NO_COLOR` isn't set.
+    pub fn detect() -> Self {
+        Self {
+            color: io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn with_legend(mut self, legend: bool) -> Self {
+        self.legend = legend;
+        self
+    }
+
+    pub fn with_viewport(mut self, viewport: Option<Rect>) -> Self {
+        self.viewport = viewport;
+        self
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+const DIM: &str = "\x1b[2m";
+
+/// A character grid with highlighted cells drawn over it, e.g. the words found in a word search.
+#[derive(Debug, Clone)]
+pub struct Canvas {
+    grid: Grid<char>,
+    marks: Grid<Option<Mark>>,
+    legend: Vec<(Color, String)>,
+    blank: Option<char>,
+    polylines: Vec<Polyline>,
+}
+
+impl Canvas {
+    pub fn new(grid: Grid<char>) -> Self {
+        let marks = Grid::new(grid.rows(), grid.cols(), None);
+        Self {
+            grid,
+            marks,
+            legend: Vec::new(),
+            blank: None,
+            polylines: Vec::new(),
+        }
+    }
+
+    /// Draw unmarked cells as `blank` when rendering without colours, so the marks stand out.
+    pub fn with_blank(mut self, blank: char) -> Self {
+        self.blank = Some(blank);
+        self
+    }
+
+    /// Highlights the cell at `point`, later marks win. Points off the grid are ignored.
+    pub fn mark(&mut self, point: Point, color: Color) {
+        self.mark_with(point, Mark { color, glyph: None });
+    }
+
+    /// Highlights the cell at `point` and draws `glyph` instead of its character.
+    pub fn mark_glyph(&mut self, point: Point, color: Color, glyph: char) {
+        self.mark_with(point, Mark { color, glyph: Some(glyph) });
+    }
+
+    pub fn mark_with(&mut self, point: Point, mark: Mark) {
+        if let Some(cell) = self.marks.get_at_mut(point) {
+            *cell = Some(mark);
+        }
+    }
+
+    pub fn get_mark(&self, point: Point) -> Option<Mark> {
+        self.marks.get_at(point).copied().flatten()
+    }
+
+    pub fn grid(&self) -> &Grid<char> {
+        &self.grid
+    }
+
+    pub fn blank(&self) -> Option<char> {
+        self.blank
+    }
+
+    pub fn legend(&self) -> &[(Color, String)] {
+        &self.legend
+    }
+
+    /// Adds a path through `points`, leaving out the points in the middle of straight stretches.
+    pub fn add_polyline(&mut self, points: impl IntoIterator<Item = Point>, color: Color) {
+        let mut corners: Vec<Point> = Vec::new();
+        for point in points {
+            if let [.., before, last] = corners[..] {
+                if (last - before).reduced() == (point - last).reduced() {
+                    corners.pop();
+                }
+            }
+            corners.push(point);
+        }
+        self.polylines.push(Polyline { points: corners, color });
+    }
+
+    pub fn polylines(&self) -> &[Polyline] {
+        &self.polylines
+    }
+
+    /// Explains what a colour stands for below the grid.
+    pub fn add_legend(&mut self, color: Color, label: impl Into<String>) {
+        self.legend.push((color, label.into()));
+    }
+
+    pub fn render(&self, options: &RenderOptions) -> String {
+        let bounds = self.grid.bounds();
+        let view = self.view(options);
+
+        let mut out = String::new();
+        for row in view.min.row..view.max.row {
+            let mut style: Option<Option<Color>> = None;
+            for col in view.min.col..view.max.col {
+                let (cell_style, c) = self.cell(Point::new(row, col), options);
+                if options.color && style != Some(cell_style) {
+                    push_style(&mut out, cell_style);
+                    style = Some(cell_style);
+                }
+                out.push(c);
+            }
+            if options.color && view.cols() > 0 {
+                out.push_str(RESET);
+            }
+            out.push('\n');
+        }
+
+        if view != bounds {
+            out.push_str(&format!(
+                "rows {}..{} of {}, columns {}..{} of {}\n",
+                view.min.row,
+                view.max.row,
+                bounds.rows(),
+                view.min.col,
+                view.max.col,
+                bounds.cols()
+            ));
+        }
+        if options.legend {
+            for (color, label) in self.legend.iter() {
+                if options.color {
+                    out.push_str(&format!("\x1b[1;{}m■{} {}\n", color.ansi_code(), RESET, label));
+                } else {
+                    out.push_str(&format!("{}: {}\n", color, label));
+                }
+            }
+        }
+        out
+    }
+}
+
+impl Canvas {
+    /// Terminal output that redraws only `points` over a frame printed by [`Canvas::render`]
+    /// at the top left of the screen, moving the cursor to each point.
+    pub fn render_updates(&self, points: &[Point], options: &RenderOptions) -> String {
+        let view = self.view(options);
+        let mut out = String::new();
+        for point in points.iter().filter(|point| view.contains(point)) {
+            let (style, c) = self.cell(*point, options);
+            out.push_str(&format!("\x1b[{};{}H", point.row - view.min.row + 1, point.col - view.min.col + 1));
+            if options.color {
+                push_style(&mut out, style);
+            }
+            out.push(c);
+        }
+        if options.color && !out.is_empty() {
+            out.push_str(RESET);
+        }
+        out
+    }
+
+    fn view(&self, options: &RenderOptions) -> Rect {
+        let bounds = self.grid.bounds();
+        options.viewport.map(|viewport| intersect(&viewport, &bounds)).unwrap_or(bounds)
+    }
+
+    /// Colour and character drawn for the cell at `point`.
+    fn cell(&self, point: Point, options: &RenderOptions) -> (Option<Color>, char) {
+        let mark = self.get_mark(point);
+        let c = match mark {
+            Some(Mark { glyph: Some(glyph), .. }) => glyph,
+            Some(_) => self.grid[point],
+            None if options.color => self.grid[point],
+            None => self.blank.unwrap_or(self.grid[point]),
+        };
+        (mark.map(|mark| mark.color), c)
+    }
+}
+
+fn push_style(out: &mut String, style: Option<Color>) {
+    out.push_str(RESET);
+    match style {
+        Some(color) => out.push_str(&format!("\x1b[1;{}m", color.ansi_code())),
+        None => out.push_str(DIM),
+    }
+}
+
+/// Writes an [asciinema](https://docs.asciinema.org/manual/asciicast/v2/) recording of a canvas
+/// changing over time: the first frame in full, then only the cells that changed.
+pub struct CastWriter<W: Write> {
+    out: W,
+    options: RenderOptions,
+}
+
+impl<W: Write> CastWriter<W> {
+    pub fn new(mut out: W, first: &Canvas, options: RenderOptions) -> io::Result<Self> {
+        let view = first.view(&options);
+        let header = serde_json::json!({
+            "version": 2,
+            "width": view.cols(),
+            "height": view.rows() + 1,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(out, "{}", header)?;
+        let mut cast = Self { out, options };
+        let frame = format!("\x1b[2J\x1b[H{}", first.render(&cast.options.with_legend(false)));
+        cast.event(Duration::ZERO, &frame.replace('\n', "\r\n"))?;
+        Ok(cast)
+    }
+
+    /// Redraws `changed` cells of `canvas` at `at` since the start of the recording.
+    pub fn frame(&mut self, at: Duration, canvas: &Canvas, changed: &[Point]) -> io::Result<()> {
+        let update = canvas.render_updates(changed, &self.options);
+        if update.is_empty() {
+            return Ok(());
+        }
+        self.event(at, &update)
+    }
+
+    fn event(&mut self, at: Duration, data: &str) -> io::Result<()> {
+        writeln!(self.out, "{}", serde_json::json!([at.as_secs_f64(), "o", data]))
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+fn intersect(a: &Rect, b: &Rect) -> Rect {
+    let min = Point::new(a.min.row.max(b.min.row), a.min.col.max(b.min.col));
+    let max = Point::new(a.max.row.min(b.max.row).max(min.row), a.max.col.min(b.max.col).max(min.col));
+    Rect::new(min, max)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn canvas() -> Canvas {
+        let mut canvas = Canvas::new(Grid::from_str("abc\ndef\nghi\n").unwrap()).with_blank('.');
+        canvas.mark(Point::new(0, 0), Color::Red);
+        canvas.mark(Point::new(0, 1), Color::Red);
+        canvas.mark_glyph(Point::new(1, 1), Color::Blue, '^');
+        canvas.mark(Point::new(5, 5), Color::Blue);
+        canvas.add_legend(Color::Red, "ab");
+        canvas
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let out = canvas().render(&RenderOptions::default());
+        assert_eq!(out, "ab.\n.^.\n...\nred: ab\n");
+        let out = canvas().render(&RenderOptions::default().with_legend(false));
+        assert_eq!(out, "ab.\n.^.\n...\n");
+        let mut canvas = Canvas::new(Grid::from_str("abc\n").unwrap());
+        canvas.mark_glyph(Point::new(0, 1), Color::Green, '*');
+        assert_eq!(canvas.render(&RenderOptions::default()), "a*c\n");
+    }
+
+    #[test]
+    fn test_render_color() {
+        let out = canvas().render(&RenderOptions::default().with_color(true).with_legend(false));
+        let first_row = out.lines().next().unwrap();
+        assert_eq!(first_row, "\x1b[0m\x1b[1;31mab\x1b[0m\x1b[2mc\x1b[0m");
+        assert!(out.contains("\x1b[1;34m^"));
+        assert_eq!(out.lines().count(), 3);
+    }
+
+    #[test]
+    fn test_polyline() {
+        let mut canvas = canvas();
+        let path = [(0, 0), (0, 1), (0, 2), (1, 2), (2, 2), (2, 1)].map(Point::from);
+        canvas.add_polyline(path, Color::Green);
+        assert_eq!(canvas.polylines()[0].points, [(0, 0), (0, 2), (2, 2), (2, 1)].map(Point::from));
+    }
+
+    #[test]
+    fn test_render_updates() {
+        let mut canvas = canvas();
+        canvas.mark(Point::new(2, 2), Color::Green);
+        let options = RenderOptions::default().with_viewport(Some(Rect::new(Point::new(1, 1), Point::new(3, 3))));
+        let out = canvas.render_updates(&[Point::new(2, 2), Point::new(0, 0), Point::new(1, 2)], &options);
+        assert_eq!(out, "\x1b[2;2Hi\x1b[1;2H.");
+        let out = canvas.render_updates(&[Point::new(2, 2)], &options.with_color(true));
+        assert_eq!(out, "\x1b[2;2H\x1b[0m\x1b[1;32mi\x1b[0m");
+    }
+
+    #[test]
+    fn test_cast() {
+        let mut canvas = canvas();
+        let mut cast = CastWriter::new(Vec::new(), &canvas, RenderOptions::default()).unwrap();
+        canvas.mark(Point::new(2, 0), Color::Green);
+        cast.frame(Duration::from_millis(500), &canvas, &[Point::new(2, 0)]).unwrap();
+        let out = String::from_utf8(cast.finish().unwrap()).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"env\":{\"TERM\":\"xterm-256color\"},\"height\":4,\"version\":2,\"width\":3}"));
+        assert_eq!(lines[1], r#"[0.0,"o","\u001b[2J\u001b[Hab.\r\n.^.\r\n...\r\n"]"#);
+        assert_eq!(lines[2], r#"[0.5,"o","\u001b[3;1Hg"]"#);
+    }
+
+    #[test]
+    fn test_viewport() {
+        let viewport = Rect::new(Point::new(1, 1), Point::new(5, 5));
+        let out = canvas().render(&RenderOptions::default().with_legend(false).with_viewport(Some(viewport)));
+        assert_eq!(out, "^.\n..\nrows 1..3 of 3, columns 1..3 of 3\n");
+        let outside = Rect::new(Point::new(7, 7), Point::new(9, 9));
+        let out = canvas().render(&RenderOptions::default().with_legend(false).with_viewport(Some(outside)));
+        assert_eq!(out, "rows 7..7 of 3, columns 7..7 of 3\n");
+    }
+}
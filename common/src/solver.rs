@@ -0,0 +1,176 @@
+use std::{
+    fmt::Display,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    inputs::{DayInputs, Input, InputCache, InputSpec},
+    render::Canvas,
+    Error,
+};
+
+/// A day's puzzle split into its stages. The input is parsed once and both parts are solved
+/// from the parsed representation, so each stage can be timed and tested on its own.
+pub trait Solution {
+    const DAY: u8;
+    const INPUTS: DayInputs;
+
+    type Parsed;
+    type Answer: Display;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error>;
+
+    fn part1(&self, parsed: &Self::Parsed) -> Self::Answer;
+
+    fn part2(&self, parsed: &Self::Parsed) -> Self::Answer;
+
+    /// The map of the puzzle with the cells that make up the answer of `part` highlighted,
+    /// for days whose input is a map.
+    fn canvas(&self, _parsed: &Self::Parsed, _part: Part) -> Option<Canvas> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Part {}", self.number())
+    }
+}
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: Part,
+    pub answer: String,
+    pub elapsed: Duration,
+}
+
+/// Outcome of solving one input: how long parsing took plus the answer and duration of each part.
+#[derive(Debug)]
+pub struct Run {
+    pub day: u8,
+    pub parse_elapsed: Duration,
+    pub parts: Vec<PartRun>,
+}
+
+/// Object safe view on a [`Solution`], which is what the `aoc` runner works with.
+/// Every [`Solution`] is a `Solver`.
+pub trait Solver {
+    fn day(&self) -> u8;
+
+    fn inputs(&self) -> DayInputs;
+
+    fn load_input(&self, spec: &InputSpec, cache: Option<&InputCache>) -> Result<Input, Error> {
+        self.inputs().load_or_fetch(self.day(), spec, cache)
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Run, Error>;
+
+    /// See [`Solution::canvas`].
+    fn canvas(&self, input: &str, part: Part) -> Result<Option<Canvas>, Error>;
+}
+
+impl<S: Solution> Solver for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn inputs(&self) -> DayInputs {
+        S::INPUTS
+    }
+
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Run, Error> {
+        let now = Instant::now();
+        let parsed = self.parse(input)?;
+        let parse_elapsed = now.elapsed();
+
+        let parts = parts
+            .iter()
+            .map(|part| {
+                let now = Instant::now();
+                let answer = match part {
+                    Part::One => self.part1(&parsed).to_string(),
+                    Part::Two => self.part2(&parsed).to_string(),
+                };
+                PartRun {
+                    part: *part,
+                    answer,
+                    elapsed: now.elapsed(),
+                }
+            })
+            .collect();
+
+        Ok(Run {
+            day: S::DAY,
+            parse_elapsed,
+            parts,
+        })
+    }
+
+    fn canvas(&self, input: &str, part: Part) -> Result<Option<Canvas>, Error> {
+        let parsed = self.parse(input)?;
+        Ok(Solution::canvas(self, &parsed, part))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        const DAY: u8 = 0;
+        const INPUTS: DayInputs = DayInputs {
+            dir: env!("CARGO_MANIFEST_DIR"),
+            embedded: &[],
+        };
+        type Parsed = Vec<i64>;
+        type Answer = i64;
+
+        fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+            input.split(",").map(|n| crate::parse_token(input, n)).collect()
+        }
+
+        fn part1(&self, parsed: &Self::Parsed) -> Self::Answer {
+            parsed.iter().sum()
+        }
+
+        fn part2(&self, parsed: &Self::Parsed) -> Self::Answer {
+            parsed.iter().product()
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let run = Sum.run("2,3,4", &[Part::Two]).unwrap();
+        assert_eq!(run.day, 0);
+        assert_eq!(run.parts.len(), 1);
+        assert_eq!(run.parts[0].part, Part::Two);
+        assert_eq!(run.parts[0].answer, "24");
+
+        let run = Sum.run("2,3,4", &Part::ALL).unwrap();
+        assert_eq!(run.parts.iter().map(|p| p.answer.as_str()).collect::<Vec<_>>(), vec!["9", "24"]);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert!(Sum.run("2,x", &Part::ALL).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use common::{day_inputs, inputs::DayInputs, parse_token, Error, Solution};
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    const DAY: u8 = 1;
+    const INPUTS: DayInputs = day_inputs!("example", "testdata");
+    type Parsed = (Vec<i64>, Vec<i64>);
+    type Answer = i64;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        make2lists(input)
+    }
+
+    fn part1(&self, lists: &Self::Parsed) -> Self::Answer {
+        sumabs(lists)
+    }
+
+    fn part2(&self, lists: &Self::Parsed) -> Self::Answer {
+        sim_score(lists)
+    }
+}
+
+fn sumabs(lists: &(Vec<i64>, Vec<i64>)) -> i64 {
+    lists.0.iter().zip(&lists.1).map(|(a, b)| (a - b).abs()).sum()
+}
+
+fn sim_score(lists: &(Vec<i64>, Vec<i64>)) -> i64 {
+    let map = make_map_with_counts(&lists.1);
+    let mut sum = 0;
+    for v in lists.0.iter() {
+        sum += map.get(v).unwrap_or(&0) * v;
+    }
+    sum
+}
+
+fn make_map_with_counts(list: &[i64]) -> HashMap<i64, i64> {
+    let mut map = HashMap::new();
+    for v in list {
+        *map.entry(*v).or_insert(0) += 1;
+    }
+    map
+}
+
+fn make2lists(data: &str) -> Result<(Vec<i64>, Vec<i64>), Error> {
+    let mut list1: Vec<i64> = Vec::new();
+    let mut list2: Vec<i64> = Vec::new();
+
+    for (i, line) in data.lines().enumerate() {
+        let (a, b) = parse_pair(line).map_err(|e| e.at_line(i + 1))?;
+        list1.push(a);
+        list2.push(b);
+    }
+
+    list1.sort();
+    list2.sort();
+    Ok((list1, list2))
+}
+
+fn parse_pair(line: &str) -> Result<(i64, i64), Error> {
+    let parts: Vec<&str> = line.split("   ").collect();
+    if parts.len() != 2 {
+        return Err(Error::syntax(line, line, "expected two location ids separated by three spaces"));
+    }
+    Ok((parse_token(line, parts[0])?, parse_token(line, parts[1])?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = Day01.parse("3   4\n4 3\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: expected two location ids separated by three spaces ('4 3')");
+        let err = Day01.parse("3   4\n4   x3\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:5: cannot parse 'x3': invalid digit found in string");
+    }
+}
@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use common::{day_inputs, inputs::DayInputs, parse_token, Error, Solution};
+
+pub struct Day02;
+
+impl Solution for Day02 {
+    const DAY: u8 = 2;
+    const INPUTS: DayInputs = day_inputs!("example", "testdata");
+    type Parsed = Vec<Report>;
+    type Answer = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| Report::from_str(line).map_err(|e| e.at_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(&self, reports: &Self::Parsed) -> Self::Answer {
+        reports.iter().filter(|report| report.is_safe()).count()
+    }
+
+    fn part2(&self, reports: &Self::Parsed) -> Self::Answer {
+        reports.iter().filter(|report| report.is_safe_dampened()).count()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Report {
+    levels: Vec<i64>,
+    direction: Direction,
+}
+
+impl FromStr for Report {
+    type Err = Error;
+
+    fn from_str(levels_str: &str) -> Result<Self, Self::Err> {
+        let levels: Vec<i64> = levels_str
+            .split(" ")
+            .map(|s| parse_token(levels_str, s))
+            .collect::<Result<_, _>>()?;
+        if levels.len() < 2 {
+            return Err(Error::syntax(levels_str, levels_str, "a report needs at least two levels"));
+        }
+        Ok(Report::new(levels))
+    }
+}
+
+impl Report {
+    pub fn new(levels: Vec<i64>) -> Self {
+        let direction = match levels[..] {
+            [first, second, ..] => Direction::from(&Pair(first, second)),
+            _ => Direction::default(),
+        };
+        Self { levels, direction }
+    }
+
+    pub fn is_safe_dampened(&self) -> bool {
+        let is_safe = self.is_safe();
+        if !is_safe {
+            for damp_index in 0..self.levels.len() {
+                let dampened_report = Report::new(self.make_dampened_levels(damp_index));
+                if dampened_report.is_safe() {
+                    return true;
+                }
+            }
+        }
+        is_safe
+    }
+
+    fn make_dampened_levels(&self, damp_index: usize) -> Vec<i64> {
+        let mut new_list = Vec::new();
+        for (i, level) in self.levels.iter().enumerate() {
+            if i != damp_index {
+                new_list.push(*level);
+            }
+        }
+        new_list
+    }
+
+    pub fn is_safe(&self) -> bool {
+        for window in self.levels.windows(2) {
+            let pair = Pair(window[0], window[1]);
+            let direction = Direction::from(&pair);
+            if direction != self.direction || pair.diff() > 3 || pair.diff() < 1 {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug)]
+pub struct Pair(i64, i64);
+
+impl Pair {
+    pub fn diff(&self) -> i64 {
+        (self.0 - self.1).abs()
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+enum Direction {
+    Increasing,
+    Decreasing,
+    #[default]
+    NotStrictlyMonotonic,
+}
+
+impl From<&Pair> for Direction {
+    fn from(pair: &Pair) -> Self {
+        if pair.0 < pair.1 {
+            return Direction::Increasing;
+        }
+        if pair.0 > pair.1 {
+            return Direction::Decreasing;
+        }
+        Direction::NotStrictlyMonotonic
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = Day02.parse("7 6 4 2 1\n1 2 a 4\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:5: cannot parse 'a': invalid digit found in string");
+        let err = Day02.parse("7 6 4 2 1\n9\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: a report needs at least two levels ('9')");
+    }
+}
@@ -0,0 +1,115 @@
+use common::{day_inputs, inputs::DayInputs, parse_token, Error, Solution};
+use regex::Regex;
+
+pub struct Day03;
+
+impl Solution for Day03 {
+    const DAY: u8 = 3;
+    const INPUTS: DayInputs = day_inputs!("example", "example2", "testdata");
+    type Parsed = Vec<Instruction>;
+    type Answer = i64;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        parse(input)
+    }
+
+    fn part1(&self, instructions: &Self::Parsed) -> Self::Answer {
+        instructions
+            .iter()
+            .map(|instruction| match instruction {
+                Instruction::Mul(mul) => mul.eval(),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Muls between a `don't()` and the next `do()` are skipped.
+    fn part2(&self, instructions: &Self::Parsed) -> Self::Answer {
+        let mut state = ParseState::default();
+        let mut sum = 0;
+        for instruction in instructions {
+            match instruction {
+                Instruction::Do => state = ParseState::Enabled,
+                Instruction::Dont => state = ParseState::Disabled,
+                Instruction::Mul(mul) => {
+                    if state == ParseState::Enabled {
+                        sum += mul.eval();
+                    }
+                }
+            }
+        }
+        sum
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+enum ParseState {
+    #[default]
+    Enabled,
+    Disabled,
+}
+
+#[derive(Debug)]
+pub enum Instruction {
+    Mul(Mul),
+    Do,
+    Dont,
+}
+
+#[derive(Debug)]
+pub struct Mul {
+    a: i64,
+    b: i64,
+}
+
+impl Mul {
+    pub fn eval(&self) -> i64 {
+        self.a * self.b
+    }
+}
+
+fn parse(s: &str) -> Result<Vec<Instruction>, Error> {
+    let re = Regex::new(r"mul\((\d+),(\d+)\)|do\(\)|don't\(\)").unwrap();
+    let mut instructions = Vec::new();
+    for (i, line) in s.lines().enumerate() {
+        for caps in re.captures_iter(line) {
+            let instruction = match &caps[0] {
+                "do()" => Instruction::Do,
+                "don't()" => Instruction::Dont,
+                _ => Instruction::Mul(Mul {
+                    a: parse_token(line, caps.get(1).unwrap().as_str()).map_err(|e| e.at_line(i + 1))?,
+                    b: parse_token(line, caps.get(2).unwrap().as_str()).map_err(|e| e.at_line(i + 1))?,
+                }),
+            };
+            instructions.push(instruction);
+        }
+    }
+    Ok(instructions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part1() {
+        let data = Day03::INPUTS.read("example").unwrap();
+        let instructions = Day03.parse(&data).unwrap();
+        assert_eq!(instructions.len(), 4);
+        assert_eq!(Day03.part1(&instructions), 161);
+    }
+
+    #[test]
+    fn test_part2() {
+        let data = Day03::INPUTS.read("example2").unwrap();
+        let instructions = Day03.parse(&data).unwrap();
+        assert_eq!(Day03.part1(&instructions), 161);
+        assert_eq!(Day03.part2(&instructions), 48);
+    }
+
+    #[test]
+    fn test_operand_overflow() {
+        let err = Day03.parse("xmul(2,4)\nmul(99999999999999999999,1)").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:5: cannot parse '99999999999999999999': number too large to fit in target type");
+    }
+}
@@ -0,0 +1,98 @@
+use aho_corasick::AhoCorasick;
+use common::{
+    geom::{Compass, Dir8, Point},
+    grid::Grid,
+};
+
+use crate::search::{wrap_point, Match, SearchOptions};
+
+/// A straight line of cells through the grid and the letters on it.
+struct Line {
+    text: String,
+    /// Cell of every char in `text`, indexed by the char's byte offset.
+    cells: Vec<Option<Point>>,
+    /// Matches have to start within this many chars, the rest only repeats the start of a wrapped line.
+    starts: usize,
+}
+
+impl Line {
+    fn new(grid: &Grid<char>, cells: &[Point], starts: usize) -> Self {
+        let mut line = Line {
+            text: String::new(),
+            cells: Vec::new(),
+            starts,
+        };
+        for cell in cells {
+            let c = grid[*cell];
+            line.cells.push(Some(*cell));
+            line.cells.extend(std::iter::repeat_n(None, c.len_utf8() - 1));
+            line.text.push(c);
+        }
+        line
+    }
+}
+
+/// Finds all words in one pass per grid line with an Aho–Corasick automaton over the whole word list.
+pub(crate) fn find(grid: &Grid<char>, words: &[&str], options: &SearchOptions) -> Vec<Match> {
+    let words: Vec<(usize, &str)> = words.iter().copied().enumerate().filter(|(_, word)| !word.is_empty()).collect();
+    if words.is_empty() {
+        return Vec::new();
+    }
+    let automaton = AhoCorasick::new(words.iter().map(|(_, word)| word)).expect("words build an automaton");
+    let longest = words.iter().map(|(_, word)| word.chars().count()).max().unwrap_or(0);
+
+    let mut matches = Vec::new();
+    for direction in options.directions.iter() {
+        for line in lines(grid, direction, options.wrap, longest) {
+            for found in automaton.find_overlapping_iter(&line.text) {
+                let start = line.text[..found.start()].chars().count();
+                if start >= line.starts {
+                    continue;
+                }
+                let cells: Vec<Point> = line.cells[found.range()].iter().flatten().copied().collect();
+                matches.push(Match {
+                    word: words[found.pattern().as_usize()].0,
+                    start: cells[0],
+                    direction,
+                    cells,
+                });
+            }
+        }
+    }
+    matches
+}
+
+/// Every line through the grid in `direction`. With `wrap` the lines are the cycles around the torus,
+/// extended by `longest - 1` letters so words crossing the start of the cycle are found too.
+fn lines(grid: &Grid<char>, direction: Dir8, wrap: bool, longest: usize) -> Vec<Line> {
+    let bounds = grid.bounds();
+    let step = direction.delta();
+    let mut lines = Vec::new();
+    if !wrap {
+        for start in bounds.points().filter(|p| !bounds.contains(&(*p - step))) {
+            let cells: Vec<Point> = std::iter::successors(Some(start), |p| Some(*p + step).filter(|p| bounds.contains(p))).collect();
+            lines.push(Line::new(grid, &cells, cells.len()));
+        }
+        return lines;
+    }
+
+    let mut seen = Grid::new(grid.rows(), grid.cols(), false);
+    for start in bounds.points() {
+        if seen[start] {
+            continue;
+        }
+        let mut cells = vec![start];
+        seen[start] = true;
+        let mut cell = wrap_point(&bounds, start + step);
+        while cell != start {
+            seen[cell] = true;
+            cells.push(cell);
+            cell = wrap_point(&bounds, cell + step);
+        }
+        let cycle = cells.len();
+        let extension: Vec<Point> = cells.iter().cycle().take(longest.saturating_sub(1)).copied().collect();
+        cells.extend(extension);
+        lines.push(Line::new(grid, &cells, cycle));
+    }
+    lines
+}
@@ -0,0 +1,192 @@
+use std::{fmt::Display, str::FromStr};
+
+use common::{
+    day_inputs,
+    geom::{Compass, Dir8, Point},
+    grid::Grid,
+    inputs::DayInputs,
+    render::{Canvas, Color},
+    Error, Part, Solution,
+};
+
+mod automaton;
+mod search;
+mod template;
+pub use search::{Match, SearchMode, SearchOptions, WordSearch};
+pub use template::{match_template, Template, TemplateMatch, Transform, WILDCARD};
+
+const MAS_CROSS: &str = "M.S\n.A.\nM.S\n";
+
+pub struct Day04;
+
+impl Solution for Day04 {
+    const DAY: u8 = 4;
+    const INPUTS: DayInputs = day_inputs!("example", "testdata");
+    type Parsed = CharGrid;
+    type Answer = i64;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        CharGrid::from_str(input)
+    }
+
+    fn part1(&self, cgrid: &Self::Parsed) -> Self::Answer {
+        cgrid.clone().find_xmas()
+    }
+
+    fn part2(&self, cgrid: &Self::Parsed) -> Self::Answer {
+        cgrid.clone().find_xmas2()
+    }
+
+    fn canvas(&self, cgrid: &Self::Parsed, part: Part) -> Option<Canvas> {
+        Some(cgrid.get_canvas(part))
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CharGrid {
+    grid: Grid<char>,
+    xmas_coords: Vec<Point>,
+}
+
+impl CharGrid {
+    /// Finds `words` in any straight line through the grid, see [`SearchOptions`].
+    pub fn search(&self, words: &[&str], options: SearchOptions) -> Vec<Match> {
+        WordSearch::new(&self.grid, options).find(words)
+    }
+
+    /// Part 2: the MAS cross in any of its rotations.
+    pub fn find_xmas2(&mut self) -> i64 {
+        self.xmas_coords.clear();
+        let cross = Template::from_str(MAS_CROSS).expect("MAS cross template is valid");
+        self.find_template(&cross, true).len() as i64
+    }
+
+    /// Finds `template` like [`match_template`] and highlights the matched letters in the result grid.
+    pub fn find_template(&mut self, template: &Template, all_transforms: bool) -> Vec<TemplateMatch> {
+        let matches = match_template(&self.grid, template, all_transforms);
+        self.xmas_coords.extend(matches.iter().flat_map(|m| m.cells.iter().copied()));
+        matches
+    }
+
+    /// Part 1: XMAS in any direction.
+    pub fn find_xmas(&mut self) -> i64 {
+        self.find_words(&["XMAS"], SearchOptions::default()).len() as i64
+    }
+
+    /// Finds `words` like [`CharGrid::search`], in either [`SearchMode`], and highlights the
+    /// matched letters in the result grid instead of those of an earlier search.
+    pub fn find_words(&mut self, words: &[&str], options: SearchOptions) -> Vec<Match> {
+        let matches = self.search(words, options);
+        self.xmas_coords.clear();
+        self.xmas_coords.extend(matches.iter().flat_map(|m| m.cells.iter().copied()));
+        matches
+    }
+
+    /// The grid with the words of `part` highlighted: XMAS coloured by direction,
+    /// or one colour per MAS cross.
+    pub fn get_canvas(&self, part: Part) -> Canvas {
+        let mut canvas = Canvas::new(self.grid.clone()).with_blank('.');
+        match part {
+            Part::One => {
+                let matches = self.search(&["XMAS"], SearchOptions::default());
+                for direction in Dir8::all() {
+                    let color = Color::nth(direction.index());
+                    let count = matches.iter().filter(|m| m.direction == direction).count();
+                    for cell in matches.iter().filter(|m| m.direction == direction).flat_map(|m| m.cells.iter()) {
+                        canvas.mark(*cell, color);
+                    }
+                    canvas.add_legend(color, format!("{} XMAS {}", count, direction));
+                }
+            }
+            Part::Two => {
+                let cross = Template::from_str(MAS_CROSS).expect("MAS cross template is valid");
+                for (i, m) in match_template(&self.grid, &cross, true).iter().enumerate() {
+                    for cell in m.cells.iter() {
+                        canvas.mark(*cell, Color::nth(i));
+                    }
+                }
+            }
+        }
+        canvas
+    }
+
+    pub fn get_result_grid(&self) -> ResultGrid {
+        let mut grid = Grid::new(self.grid.rows(), self.grid.cols(), '.');
+        for xmas_coord in self.xmas_coords.iter() {
+            grid[*xmas_coord] = self.grid[*xmas_coord];
+        }
+
+        ResultGrid::new(grid)
+    }
+}
+
+impl FromStr for CharGrid {
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            grid: Grid::from_str(data)?,
+            ..Default::default()
+        })
+    }
+}
+
+pub struct ResultGrid {
+    grid: Grid<char>,
+}
+
+impl ResultGrid {
+    fn new(grid: Grid<char>) -> Self {
+        Self { grid }
+    }
+}
+
+impl Display for ResultGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::render::RenderOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let grid = Day04.parse(&Day04::INPUTS.read("example").unwrap()).unwrap();
+        assert_eq!(Day04.part1(&grid), 18);
+        assert_eq!(Day04.part2(&grid), 9);
+    }
+
+    #[test]
+    fn test_automaton_coords() {
+        let data = Day04::INPUTS.read("example").unwrap();
+        let mut scanned = CharGrid::from_str(&data).unwrap();
+        let mut automaton = scanned.clone();
+        assert_eq!(scanned.find_xmas(), 18);
+        let expected = scanned.get_result_grid().to_string();
+        let matches = automaton.find_words(&["XMAS"], SearchOptions::default().with_mode(SearchMode::Automaton));
+        assert_eq!(matches.len(), 18);
+        assert_eq!(automaton.get_result_grid().to_string(), expected);
+
+        // A second search replaces the highlights of the first.
+        assert_eq!(scanned.find_xmas(), 18);
+        assert_eq!(scanned.get_result_grid().to_string(), expected);
+        assert_eq!(scanned.xmas_coords.len(), 18 * 4);
+        automaton.find_words(&["SAMX"], SearchOptions::default().with_mode(SearchMode::Automaton).with_directions([Dir8::Right]));
+        assert_eq!(automaton.xmas_coords.len(), 4 * 2);
+    }
+
+    #[test]
+    fn test_canvas() {
+        let grid = Day04.parse(&Day04::INPUTS.read("example").unwrap()).unwrap();
+        let mut part1 = grid.clone();
+        part1.find_xmas();
+        let options = RenderOptions::default().with_legend(false);
+        assert_eq!(grid.get_canvas(Part::One).render(&options), part1.get_result_grid().to_string());
+        let legend = grid.get_canvas(Part::One).render(&RenderOptions::default());
+        assert!(legend.ends_with("red: 2 XMAS ↑\ngreen: 4 XMAS ↗\nyellow: 3 XMAS →\nblue: 1 XMAS ↘\nmagenta: 1 XMAS ↓\ncyan: 1 XMAS ↙\nbright red: 2 XMAS ←\nbright green: 4 XMAS ↖\n"));
+    }
+}
@@ -0,0 +1,235 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::automaton;
+
+use common::{
+    geom::{Compass, Dir8, DirSet, Point, Rect},
+    grid::Grid,
+};
+
+/// How [`WordSearch::find`] looks for words.
+#[derive(Debug, Clone, Copy)]
+pub struct SearchOptions {
+    /// Words continue on the opposite edge when they run off the grid.
+    pub wrap: bool,
+    /// Directions a word may be spelled in.
+    pub directions: DirSet<Dir8>,
+    /// Matches may share cells. Without overlapping the first match in reading order wins.
+    pub overlapping: bool,
+    pub mode: SearchMode,
+}
+
+/// How [`WordSearch::find`] walks the grid. Both modes find the same matches.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SearchMode {
+    /// Try every word in every direction from each cell holding the word's first letter.
+    #[default]
+    Scan,
+    /// Run an Aho–Corasick automaton over all words along every row, column and diagonal,
+    /// one pass per line however long the word list is.
+    Automaton,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            wrap: false,
+            directions: Dir8::all().collect(),
+            overlapping: true,
+            mode: SearchMode::Scan,
+        }
+    }
+}
+
+impl SearchOptions {
+    pub fn with_wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    pub fn with_directions(mut self, directions: impl IntoIterator<Item = Dir8>) -> Self {
+        self.directions = directions.into_iter().collect();
+        self
+    }
+
+    pub fn with_overlapping(mut self, overlapping: bool) -> Self {
+        self.overlapping = overlapping;
+        self
+    }
+
+    pub fn with_mode(mut self, mode: SearchMode) -> Self {
+        self.mode = mode;
+        self
+    }
+}
+
+/// A word found in the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Match {
+    /// Index into the searched words.
+    pub word: usize,
+    pub start: Point,
+    pub direction: Dir8,
+    /// The cells spelling the word, starting with `start`.
+    pub cells: Vec<Point>,
+}
+
+/// Finds words spelled in straight lines through a grid of letters.
+pub struct WordSearch<'a> {
+    grid: &'a Grid<char>,
+    options: SearchOptions,
+}
+
+impl<'a> WordSearch<'a> {
+    pub fn new(grid: &'a Grid<char>, options: SearchOptions) -> Self {
+        Self { grid, options }
+    }
+
+    /// All matches of `words`, in reading order of their start cells, then by direction and word.
+    pub fn find(&self, words: &[&str]) -> Vec<Match> {
+        let mut matches = match self.options.mode {
+            SearchMode::Scan => self.scan(words),
+            SearchMode::Automaton => {
+                let mut matches = automaton::find(self.grid, words, &self.options);
+                matches.sort_by_key(|m| (m.start, m.direction, m.word));
+                matches
+            }
+        };
+        if !self.options.overlapping {
+            let mut used: HashSet<Point> = HashSet::new();
+            matches.retain(|m| {
+                if m.cells.iter().any(|cell| used.contains(cell)) {
+                    return false;
+                }
+                used.extend(m.cells.iter().copied());
+                true
+            });
+        }
+        matches
+    }
+
+    fn scan(&self, words: &[&str]) -> Vec<Match> {
+        let words: Vec<Vec<char>> = words.iter().map(|word| word.chars().collect()).collect();
+        let mut by_first_char: HashMap<char, Vec<usize>> = HashMap::new();
+        for (i, word) in words.iter().enumerate() {
+            if let Some(first) = word.first() {
+                by_first_char.entry(*first).or_default().push(i);
+            }
+        }
+
+        let mut matches = Vec::new();
+        for ((row, col), c) in self.grid.iter() {
+            let Some(candidates) = by_first_char.get(c) else {
+                continue;
+            };
+            let start = Point::from((row, col));
+            for direction in self.options.directions.iter() {
+                for &word in candidates {
+                    if let Some(cells) = self.spells(&words[word], start, direction) {
+                        matches.push(Match { word, start, direction, cells });
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    /// The cells spelling `word` from `start` in `direction`, if they do.
+    fn spells(&self, word: &[char], start: Point, direction: Dir8) -> Option<Vec<Point>> {
+        let cell = |i: usize| {
+            let cell = start + direction.delta() * i as i64;
+            if self.options.wrap {
+                wrap_point(&self.grid.bounds(), cell)
+            } else {
+                cell
+            }
+        };
+        // Check before collecting, almost every start fails on the second letter.
+        if word.iter().enumerate().all(|(i, c)| self.grid.get_at(cell(i)) == Some(c)) {
+            Some((0..word.len()).map(cell).collect())
+        } else {
+            None
+        }
+    }
+}
+
+/// `point` moved onto the grid as if its edges were glued together.
+pub(crate) fn wrap_point(bounds: &Rect, point: Point) -> Point {
+    Point::new(point.row.rem_euclid(bounds.rows()), point.col.rem_euclid(bounds.cols()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    const GRID: &str = "ABC\nDEF\nGHI\n";
+
+    #[test]
+    fn test_find_words() {
+        let grid = Grid::from_str(GRID).unwrap();
+        let search = WordSearch::new(&grid, SearchOptions::default());
+        let matches = search.find(&["AEI", "FED", "CEG", "XYZ"]);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(
+            matches[0],
+            Match {
+                word: 0,
+                start: Point::new(0, 0),
+                direction: Dir8::DownRight,
+                cells: vec![Point::new(0, 0), Point::new(1, 1), Point::new(2, 2)],
+            }
+        );
+        assert_eq!((matches[1].word, matches[1].direction), (2, Dir8::DownLeft));
+        assert_eq!((matches[2].word, matches[2].direction), (1, Dir8::Left));
+    }
+
+    #[test]
+    fn test_directions() {
+        let grid = Grid::from_str(GRID).unwrap();
+        let options = SearchOptions::default().with_directions([Dir8::Right, Dir8::Down]);
+        let matches = WordSearch::new(&grid, options).find(&["ABC", "CBA", "ADG", "AEI"]);
+        assert_eq!(matches.iter().map(|m| m.word).collect::<Vec<_>>(), vec![0, 2]);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let grid = Grid::from_str(GRID).unwrap();
+        let words = ["CAB", "IA", "GA"];
+        assert!(WordSearch::new(&grid, SearchOptions::default()).find(&words).is_empty());
+        let matches = WordSearch::new(&grid, SearchOptions::default().with_wrap(true)).find(&words);
+        assert_eq!(matches.len(), 3);
+        assert_eq!(matches[0].cells, vec![Point::new(0, 2), Point::new(0, 0), Point::new(0, 1)]);
+    }
+
+    #[test]
+    fn test_modes_agree() {
+        let grid = Grid::from_str("XMASX\nSAMXM\nAMXSA\nMASXS\nXSAMX\n").unwrap();
+        let words = ["XMAS", "SAM", "AXA", "MM", "X", "SAMXSAMX"];
+        for wrap in [false, true] {
+            for overlapping in [true, false] {
+                for directions in [Dir8::ALL.to_vec(), vec![Dir8::Right, Dir8::DownLeft, Dir8::Up]] {
+                    let options = SearchOptions::default()
+                        .with_wrap(wrap)
+                        .with_overlapping(overlapping)
+                        .with_directions(directions);
+                    let scanned = WordSearch::new(&grid, options).find(&words);
+                    let automaton = WordSearch::new(&grid, options.with_mode(SearchMode::Automaton)).find(&words);
+                    assert!(!scanned.is_empty());
+                    assert_eq!(scanned, automaton, "wrap: {}, overlapping: {}", wrap, overlapping);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlapping() {
+        let grid = Grid::from_str("AAA\n").unwrap();
+        assert_eq!(WordSearch::new(&grid, SearchOptions::default()).find(&["AA"]).len(), 4);
+        let options = SearchOptions::default().with_overlapping(false);
+        let matches = WordSearch::new(&grid, options).find(&["AA"]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].cells, vec![Point::new(0, 0), Point::new(0, 1)]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::{fmt::Display, str::FromStr};
+
+use common::{
+    geom::{Point, Vector},
+    grid::Grid,
+    Error,
+};
+
+/// Character that matches any letter in a [`Template`].
+pub const WILDCARD: char = '.';
+
+/// How a template was turned before it matched: mirrored left to right first, then rotated clockwise.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transform {
+    pub quarter_turns: u8,
+    pub reflected: bool,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        quarter_turns: 0,
+        reflected: false,
+    };
+
+    /// The four rotations, followed by the four rotations of the mirror image.
+    pub fn all() -> impl Iterator<Item = Transform> {
+        [false, true]
+            .into_iter()
+            .flat_map(|reflected| (0..4).map(move |quarter_turns| Transform { quarter_turns, reflected }))
+    }
+}
+
+impl Display for Transform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.quarter_turns, self.reflected) {
+            (0, false) => write!(f, "as is"),
+            (0, true) => write!(f, "mirrored"),
+            (turns, false) => write!(f, "rotated {}°", turns as u32 * 90),
+            (turns, true) => write!(f, "mirrored, rotated {}°", turns as u32 * 90),
+        }
+    }
+}
+
+/// A small pattern of letters and [`WILDCARD`]s, e.g. the MAS cross:
+///
+/// ```text
+/// M.S
+/// .A.
+/// M.S
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Template {
+    cells: Grid<Option<char>>,
+}
+
+impl Template {
+    pub fn transformed(&self, transform: Transform) -> Template {
+        let mut cells = if transform.reflected { self.cells.flip_horizontal() } else { self.cells.clone() };
+        for _ in 0..transform.quarter_turns % 4 {
+            cells = cells.rotate_right();
+        }
+        Template { cells }
+    }
+
+    /// The template under every transform that gives a different pattern, the first transform wins.
+    pub fn variants(&self) -> Vec<(Transform, Template)> {
+        let mut variants: Vec<(Transform, Template)> = Vec::new();
+        for transform in Transform::all() {
+            let template = self.transformed(transform);
+            if variants.iter().all(|(_, seen)| *seen != template) {
+                variants.push((transform, template));
+            }
+        }
+        variants
+    }
+
+    /// Offsets of the letters from the top left corner.
+    fn letters(&self) -> impl Iterator<Item = (Vector, char)> + '_ {
+        self.cells
+            .iter()
+            .filter_map(|((row, col), c)| c.map(|c| (Vector::new(row as i64, col as i64), c)))
+    }
+}
+
+impl FromStr for Template {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cells = Grid::parse_with(s, |c| (c != WILDCARD).then_some(c))?;
+        Ok(Template { cells })
+    }
+}
+
+impl Display for Template {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.cells.map(|c| c.unwrap_or(WILDCARD)))
+    }
+}
+
+/// A template found in the grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateMatch {
+    /// Top left corner of the transformed template.
+    pub at: Point,
+    pub transform: Transform,
+    /// Grid cells under the template's letters.
+    pub cells: Vec<Point>,
+}
+
+/// All places where `template` fits on `grid`, with every transform of it if `all_transforms` is set.
+pub fn match_template(grid: &Grid<char>, template: &Template, all_transforms: bool) -> Vec<TemplateMatch> {
+    let variants = if all_transforms {
+        template.variants()
+    } else {
+        vec![(Transform::IDENTITY, template.clone())]
+    };
+    let variants: Vec<(Transform, Vec<(Vector, char)>)> = variants
+        .into_iter()
+        .map(|(transform, template)| (transform, template.letters().collect()))
+        .collect();
+
+    let mut matches = Vec::new();
+    for at in grid.bounds().points() {
+        for (transform, letters) in variants.iter() {
+            if letters.iter().all(|(offset, c)| grid.get_at(at + *offset) == Some(c)) {
+                matches.push(TemplateMatch {
+                    at,
+                    transform: *transform,
+                    cells: letters.iter().map(|(offset, _)| at + *offset).collect(),
+                });
+            }
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAS_CROSS: &str = "M.S\n.A.\nM.S\n";
+
+    #[test]
+    fn test_variants() {
+        let cross = Template::from_str(MAS_CROSS).unwrap();
+        assert_eq!(cross.to_string(), MAS_CROSS);
+        let variants = cross.variants();
+        assert_eq!(variants.len(), 4);
+        assert_eq!(variants[1].0.to_string(), "rotated 90°");
+        assert_eq!(variants[1].1.to_string(), "M.M\n.A.\nS.S\n");
+        assert_eq!(Template::from_str("ab\nc.\n").unwrap().variants().len(), 8);
+        assert_eq!(Template::from_str("...\n.x.\n...\n").unwrap().variants().len(), 1);
+    }
+
+    #[test]
+    fn test_match_template() {
+        let grid = Grid::from_str("MAS\nAAM\nSMS\n").unwrap();
+        let plus = Template::from_str(".A.\nAAM\n.M.\n").unwrap();
+        let matches = match_template(&grid, &plus, false);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].at, Point::new(0, 0));
+        assert_eq!(matches[0].cells.len(), 5);
+
+        let block = Template::from_str("SA\nMA\n").unwrap();
+        assert!(match_template(&grid, &block, false).is_empty());
+        let matches = match_template(&grid, &block, true);
+        let found: Vec<(Point, String)> = matches.iter().map(|m| (m.at, m.transform.to_string())).collect();
+        assert_eq!(found, vec![(Point::new(0, 1), "mirrored".to_string()), (Point::new(1, 0), "rotated 270°".to_string())]);
+    }
+}
@@ -0,0 +1,241 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
+
+use common::{day_inputs, inputs::DayInputs, parse_token, Error, Solution};
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    const DAY: u8 = 5;
+    const INPUTS: DayInputs = day_inputs!("example", "testdata");
+    type Parsed = PrintQueue;
+    type Answer = i64;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        PrintQueue::from_input(input)
+    }
+
+    fn part1(&self, queue: &Self::Parsed) -> Self::Answer {
+        queue
+            .updates
+            .iter()
+            .filter(|update| update.is_correctly_ordered(&queue.rules))
+            .map(|update| update.get_middle_page_no())
+            .sum()
+    }
+
+    fn part2(&self, queue: &Self::Parsed) -> Self::Answer {
+        let mut sum_middle_numbers = 0;
+        for update in queue.updates.iter() {
+            if !update.is_correctly_ordered(&queue.rules) {
+                sum_middle_numbers += update.sorted(&queue.rules).get_middle_page_no();
+            }
+        }
+        sum_middle_numbers
+    }
+}
+
+/// The ordering rules together with the updates.
+#[derive(Debug, Default)]
+pub struct PrintQueue {
+    rules: OrderRules,
+    updates: Vec<PageUpdate>,
+}
+
+impl PrintQueue {
+    fn from_input(data: &str) -> Result<Self, Error> {
+        let rules = OrderRules::from_input(data)?;
+        let updates = PageUpdate::page_updates_from(data, &rules)?;
+        Ok(Self { rules, updates })
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+struct PageUpdate {
+    pages: Vec<i64>,
+}
+
+impl PageUpdate {
+    /// Parses an update and checks that it has a middle page and that the rules order every two
+    /// of its pages.
+    fn from_csv(csv_line: &str, order_rules: &OrderRules) -> Result<Self, Error> {
+        let tokens: Vec<&str> = csv_line.split(",").collect();
+        let pages: Vec<i64> = tokens.iter().map(|p| parse_token(csv_line, p)).collect::<Result<_, _>>()?;
+        if pages.len().is_multiple_of(2) {
+            return Err(Error::syntax(csv_line, csv_line, "expected an odd number of pages"));
+        }
+        for (token, page_no) in tokens.iter().zip(&pages) {
+            if let Some(other) = pages.iter().find(|other| !order_rules.orders(*page_no, **other)) {
+                return Err(Error::syntax(csv_line, token, format!("no order rule for pages {} and {}", page_no, other)));
+            }
+        }
+        Ok(PageUpdate { pages })
+    }
+
+    fn is_correctly_ordered(&self, order_rules: &OrderRules) -> bool {
+        for i in 0..self.pages.iter().len() {
+            for j in (i + 1)..self.pages.len() {
+                if order_rules.compare(self.pages[i], self.pages[j]) == Ordering::Greater {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// The update with its pages in the order the rules ask for.
+    fn sorted(&self, order_rules: &OrderRules) -> Self {
+        let mut pages = self.pages.clone();
+        pages.sort_by(|a, b| order_rules.compare(*a, *b));
+        PageUpdate { pages }
+    }
+
+    fn page_updates_from(data: &str, order_rules: &OrderRules) -> Result<Vec<Self>, Error> {
+        let mut in_page_updates = false;
+        let mut updates: Vec<Self> = Vec::new();
+        for (i, line) in data.lines().enumerate() {
+            if line.is_empty() {
+                in_page_updates = true;
+                continue;
+            }
+            if in_page_updates {
+                updates.push(PageUpdate::from_csv(line, order_rules).map_err(|e| e.at_line(i + 1))?);
+            }
+        }
+        Ok(updates)
+    }
+
+    fn get_middle_page_no(&self) -> i64 {
+        self.pages[self.pages.len() / 2]
+    }
+}
+
+#[derive(Debug, Default)]
+struct OrderRules {
+    rule_map: HashMap<i64, HashSet<i64>>,
+}
+
+impl OrderRules {
+    fn new() -> Self {
+        OrderRules::default()
+    }
+
+    fn from_input(data: &str) -> Result<Self, Error> {
+        let mut rules = OrderRules::new();
+        for (i, line) in data.lines().enumerate() {
+            if line.is_empty() {
+                break;
+            }
+            rules.add(line).map_err(|e| e.at_line(i + 1))?;
+        }
+        Ok(rules)
+    }
+
+    /// Whether a rule puts `page_no` in front of `other`.
+    fn precedes(&self, page_no: i64, other: i64) -> bool {
+        self.rule_map.get(&page_no).is_some_and(|followers| followers.contains(&other))
+    }
+
+    /// Whether the rules say which of the two pages comes first, or they are the same page.
+    fn orders(&self, page_no: i64, other: i64) -> bool {
+        page_no == other || self.precedes(page_no, other) || self.precedes(other, page_no)
+    }
+
+    /// The order of two pages of an update, which [`PageUpdate::from_csv`] checked the rules know.
+    fn compare(&self, page_no: i64, other: i64) -> Ordering {
+        if page_no == other {
+            Ordering::Equal
+        } else if self.precedes(page_no, other) {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    }
+
+    fn add(&mut self, rule: &str) -> Result<(), Error> {
+        let Some((page_no, following_page_no)) = rule.split_once("|") else {
+            return Err(Error::syntax(rule, rule, "expected an order rule like '47|53'"));
+        };
+        let page_no: i64 = parse_token(rule, page_no)?;
+        let following_page_no: i64 = parse_token(rule, following_page_no)?;
+        self.rule_map.entry(page_no).or_default().insert(following_page_no);
+        self.rule_map.entry(following_page_no).or_default();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_frominput() {
+        let data = Day05::INPUTS.read("example").unwrap();
+        let r = OrderRules::from_input(&data).unwrap();
+        assert_eq!(r.rule_map[&97], HashSet::from_iter(vec![13, 61, 47, 29, 53, 75]));
+        assert_eq!(r.rule_map.len(), 7);
+    }
+
+    #[test]
+    fn test_pageupdate_from_csv_line() {
+        let data = Day05::INPUTS.read("example").unwrap();
+        let rules = OrderRules::from_input(&data).unwrap();
+        let pu = PageUpdate::from_csv("75,47,61,53,29", &rules).unwrap();
+        assert_eq!(pu.pages.len(), 5);
+        assert_eq!(pu.pages, [75, 47, 61, 53, 29]);
+    }
+
+    #[test]
+    fn test_get_middle_pageno() {
+        let data = Day05::INPUTS.read("example").unwrap();
+        let rules = OrderRules::from_input(&data).unwrap();
+        let pu = PageUpdate::from_csv("75,47,61,53,29", &rules).unwrap();
+        assert_eq!(pu.get_middle_page_no(), 61);
+        let pu = PageUpdate::from_csv("75,29,13", &rules).unwrap();
+        assert_eq!(pu.get_middle_page_no(), 29);
+    }
+
+    #[test]
+    fn test_malformed_updates() {
+        let err = PrintQueue::from_input("1|2\n\n1,2\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:3:1: expected an odd number of pages ('1,2')");
+        let err = PrintQueue::from_input("1|2\n\n3,4,5\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:3:1: no order rule for pages 3 and 4");
+        let err = PrintQueue::from_input("47|53\n53|13\n\n47\n47,13,53\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:5:1: no order rule for pages 47 and 13");
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = PrintQueue::from_input("47|53\n97-13\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: expected an order rule like '47|53' ('97-13')");
+        let err = PrintQueue::from_input("47|53\n\n47\n53,4x,47\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:4:4: cannot parse '4x': invalid digit found in string");
+    }
+
+    #[test]
+    fn test_pageupdate_from_data() {
+        let data = Day05::INPUTS.read("example").unwrap();
+        let rules = OrderRules::from_input(&data).unwrap();
+        let pus = PageUpdate::page_updates_from(&data, &rules).unwrap();
+        assert_eq!(pus.len(), 6);
+        assert_eq!(pus[0].pages, [75, 47, 61, 53, 29]);
+    }
+
+    #[test]
+    fn test_pageupdate_correctly_ordered() {
+        let data = Day05::INPUTS.read("example").unwrap();
+        let rules = OrderRules::from_input(&data).unwrap();
+        let pu = PageUpdate::from_csv("75,47,61,53,29", &rules).unwrap();
+        assert!(pu.is_correctly_ordered(&rules));
+        let pu = PageUpdate::from_csv("75,97,47,61,53", &rules).unwrap();
+        assert!(!pu.is_correctly_ordered(&rules));
+        let pu = PageUpdate::from_csv("61,13,29", &rules).unwrap();
+        assert!(!pu.is_correctly_ordered(&rules));
+        let pu = PageUpdate::from_csv("97,13,75,29,47", &rules).unwrap();
+        assert!(!pu.is_correctly_ordered(&rules));
+    }
+}
@@ -0,0 +1,305 @@
+use common::geom::{Compass, Dir4, Point};
+
+use crate::CharGrid;
+
+/// Marks a guard walking off the map in [`JumpTable`].
+const EXIT: u32 = u32::MAX;
+
+/// For every cell and direction the cell where a guard walking that way stops in front of an
+/// obstacle, so a walk takes one lookup per turn instead of one per step. Made for the puzzle's
+/// maps: one-way cells and portals, which [`crate::Day06`] rejects, are walked over like free
+/// cells.
+#[derive(Debug, Clone)]
+pub struct JumpTable {
+    rows: usize,
+    cols: usize,
+    /// Indexed by `cell * 4 + direction`.
+    stops: Vec<u32>,
+    obstacles: Vec<bool>,
+}
+
+impl JumpTable {
+    pub fn new(grid: &CharGrid) -> Self {
+        let (rows, cols) = (grid.grid.rows(), grid.grid.cols());
+        let obstacles: Vec<bool> = grid.grid.iter().map(|((row, col), _)| grid.is_obstacle(&Point::from((row, col)))).collect();
+        let mut table = JumpTable {
+            rows,
+            cols,
+            stops: vec![EXIT; rows * cols * 4],
+            obstacles,
+        };
+        for dir in Dir4::all() {
+            // Visit the cells so the one ahead of each cell already has its stop.
+            let mut cells: Vec<usize> = (0..rows * cols).collect();
+            match dir {
+                Dir4::Down | Dir4::Right => cells.reverse(),
+                Dir4::Up | Dir4::Left => {}
+            }
+            for cell in cells {
+                let stop = match table.ahead(cell, dir) {
+                    None => EXIT,
+                    Some(ahead) if table.obstacles[ahead] => cell as u32,
+                    Some(ahead) => table.stops[ahead * 4 + dir.index()],
+                };
+                table.stops[cell * 4 + dir.index()] = stop;
+            }
+        }
+        table
+    }
+
+    pub fn cell(&self, point: Point) -> usize {
+        point.row as usize * self.cols + point.col as usize
+    }
+
+    pub fn point(&self, cell: usize) -> Point {
+        Point::from((cell / self.cols, cell % self.cols))
+    }
+
+    pub fn cells(&self) -> usize {
+        self.rows * self.cols
+    }
+
+    /// The next cell in `dir`, `None` at the edge of the map.
+    fn ahead(&self, cell: usize, dir: Dir4) -> Option<usize> {
+        let (row, col) = (cell / self.cols, cell % self.cols);
+        match dir {
+            Dir4::Up => row.checked_sub(1).map(|row| row * self.cols + col),
+            Dir4::Down => (row + 1 < self.rows).then_some(cell + self.cols),
+            Dir4::Left => col.checked_sub(1).map(|col| row * self.cols + col),
+            Dir4::Right => (col + 1 < self.cols).then_some(cell + 1),
+        }
+    }
+
+    /// Where the guard stops walking from `cell` in `dir`, `None` if it leaves the map.
+    pub fn stop(&self, cell: usize, dir: Dir4) -> Option<usize> {
+        let stop = self.stops[cell * 4 + dir.index()];
+        (stop != EXIT).then_some(stop as usize)
+    }
+
+    /// Like [`JumpTable::stop`] with an extra obstacle at `obstacle`.
+    pub fn stop_with(&self, cell: usize, dir: Dir4, obstacle: usize) -> Option<usize> {
+        let stop = self.stop(cell, dir);
+        let (row, col) = (cell / self.cols, cell % self.cols);
+        let (o_row, o_col) = (obstacle / self.cols, obstacle % self.cols);
+        // Distance to the obstacle along the walk, if it is ahead in the same row or column.
+        let distance = match dir {
+            Dir4::Up if o_col == col && o_row < row => row - o_row,
+            Dir4::Down if o_col == col && o_row > row => o_row - row,
+            Dir4::Left if o_row == row && o_col < col => col - o_col,
+            Dir4::Right if o_row == row && o_col > col => o_col - col,
+            _ => return stop,
+        };
+        let reach = match stop {
+            None => usize::MAX,
+            Some(stop) => (stop / self.cols).abs_diff(row) + (stop % self.cols).abs_diff(col),
+        };
+        // The guard stops in front of the new obstacle if it comes before the old stop.
+        if distance <= reach {
+            Some(self.step_back(obstacle, dir))
+        } else {
+            stop
+        }
+    }
+
+    /// Walks from `cell` in `dir` with an extra obstacle until the guard leaves the map or
+    /// stops somewhere it stopped before in the same direction.
+    pub fn loops_with(&self, cell: usize, dir: Dir4, obstacle: usize, visited: &mut StateSet) -> bool {
+        visited.clear();
+        let (mut cell, mut dir) = (cell, dir);
+        while let Some(stop) = self.stop_with(cell, dir, obstacle) {
+            if !visited.insert(stop, dir) {
+                return true;
+            }
+            cell = stop;
+            dir = dir.turn_right();
+        }
+        false
+    }
+
+    /// The cell before `cell` when walking in `dir`.
+    fn step_back(&self, cell: usize, dir: Dir4) -> usize {
+        match dir {
+            Dir4::Up => cell + self.cols,
+            Dir4::Down => cell - self.cols,
+            Dir4::Left => cell + 1,
+            Dir4::Right => cell - 1,
+        }
+    }
+}
+
+/// Dense bitset of `(cell, direction)` states that remembers which words to clear.
+#[derive(Debug, Clone)]
+pub struct StateSet {
+    bits: Vec<u64>,
+    touched: Vec<usize>,
+}
+
+impl StateSet {
+    pub fn new(cells: usize) -> Self {
+        Self {
+            bits: vec![0; (cells * 4).div_ceil(64)],
+            touched: Vec::new(),
+        }
+    }
+
+    /// Adds the state, returns `false` if it was already in the set.
+    pub fn insert(&mut self, cell: usize, dir: Dir4) -> bool {
+        let state = cell * 4 + dir.index();
+        let (word, bit) = (state / 64, 1 << (state % 64));
+        if self.bits[word] & bit != 0 {
+            return false;
+        }
+        if self.bits[word] == 0 {
+            self.touched.push(word);
+        }
+        self.bits[word] |= bit;
+        true
+    }
+
+    pub fn clear(&mut self) {
+        for word in self.touched.drain(..) {
+            self.bits[word] = 0;
+        }
+    }
+}
+
+/// A cell the guard walks onto for the first time, and the state right in front of it.
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    cell: usize,
+    dir: Dir4,
+    obstacle: usize,
+}
+
+/// Walks the original path once. Only cells on it can change the guard's walk, and the walk up
+/// to the first time the guard would enter such a cell stays the same, so each check starts
+/// right in front of it. Returns the cells to check and the free cells that make the guard loop
+/// without a check, because it loops by itself and never reaches them.
+fn candidates(table: &JumpTable, guard: Point, direction: Dir4) -> (Vec<Candidate>, Vec<Point>) {
+    let mut path = StateSet::new(table.cells());
+    let mut seen = vec![false; table.cells()];
+    let mut candidates = Vec::new();
+
+    let (mut cell, mut dir) = (table.cell(guard), direction);
+    seen[cell] = true;
+    loop {
+        if !path.insert(cell, dir) {
+            let unreached = (0..table.cells()).filter(|cell| !seen[*cell] && !table.obstacles[*cell]).map(|cell| table.point(cell));
+            return (candidates, unreached.collect());
+        }
+        let Some(ahead) = table.ahead(cell, dir) else {
+            return (candidates, Vec::new());
+        };
+        if table.obstacles[ahead] {
+            dir = dir.turn_right();
+            continue;
+        }
+        if !seen[ahead] {
+            seen[ahead] = true;
+            candidates.push(Candidate { cell, dir, obstacle: ahead });
+        }
+        cell = ahead;
+    }
+}
+
+/// Free cells where an extra obstacle makes the guard walk in a loop, in reading order.
+/// Spread across threads with the `parallel` feature. Like [`JumpTable`], doesn't support
+/// one-way cells and portals.
+pub fn loop_obstacles(grid: &CharGrid) -> Vec<Point> {
+    #[cfg(feature = "parallel")]
+    let loop_obstacles = loop_obstacles_parallel(grid);
+    #[cfg(not(feature = "parallel"))]
+    let loop_obstacles = loop_obstacles_serial(grid);
+    loop_obstacles
+}
+
+/// [`loop_obstacles`] on the current thread.
+pub fn loop_obstacles_serial(grid: &CharGrid) -> Vec<Point> {
+    let table = JumpTable::new(grid);
+    let (candidates, mut loop_obstacles) = candidates(&table, grid.guard, grid.direction);
+    let mut visited = StateSet::new(table.cells());
+    for candidate in candidates {
+        if table.loops_with(candidate.cell, candidate.dir, candidate.obstacle, &mut visited) {
+            loop_obstacles.push(table.point(candidate.obstacle));
+        }
+    }
+    loop_obstacles.sort();
+    loop_obstacles
+}
+
+/// [`loop_obstacles`] with the candidates checked on the rayon pool, one [`StateSet`] per job.
+#[cfg(feature = "parallel")]
+pub fn loop_obstacles_parallel(grid: &CharGrid) -> Vec<Point> {
+    use rayon::prelude::*;
+
+    let table = JumpTable::new(grid);
+    let (candidates, mut loop_obstacles) = candidates(&table, grid.guard, grid.direction);
+    loop_obstacles.par_extend(
+        candidates
+            .par_iter()
+            .map_init(
+                || StateSet::new(table.cells()),
+                |visited, candidate| table.loops_with(candidate.cell, candidate.dir, candidate.obstacle, visited).then(|| table.point(candidate.obstacle)),
+            )
+            .flatten(),
+    );
+    loop_obstacles.sort();
+    loop_obstacles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day06;
+    use common::Solution;
+
+    fn example() -> CharGrid {
+        Day06.parse(&Day06::INPUTS.read("example").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_stops() {
+        let grid = example();
+        let table = JumpTable::new(&grid);
+        let guard = table.cell(grid.guard);
+        assert_eq!(table.stop(guard, Dir4::Up).map(|cell| table.point(cell)), Some(Point::new(1, 4)));
+        assert_eq!(table.stop(guard, Dir4::Left).map(|cell| table.point(cell)), Some(Point::new(6, 2)));
+        assert_eq!(table.stop(guard, Dir4::Down), None);
+        assert_eq!(table.stop(guard, Dir4::Right), None);
+
+        let obstacle = table.cell(Point::new(3, 4));
+        assert_eq!(table.stop_with(guard, Dir4::Up, obstacle).map(|cell| table.point(cell)), Some(Point::new(4, 4)));
+        let obstacle = table.cell(Point::new(0, 4));
+        assert_eq!(table.stop_with(guard, Dir4::Up, obstacle).map(|cell| table.point(cell)), Some(Point::new(1, 4)));
+        let obstacle = table.cell(Point::new(6, 9));
+        assert_eq!(table.stop_with(guard, Dir4::Right, obstacle).map(|cell| table.point(cell)), Some(Point::new(6, 8)));
+        let obstacle = table.cell(Point::new(7, 4));
+        assert_eq!(table.stop_with(guard, Dir4::Up, obstacle).map(|cell| table.point(cell)), Some(Point::new(1, 4)));
+    }
+
+    #[test]
+    fn test_state_set() {
+        let mut set = StateSet::new(100);
+        assert!(set.insert(99, Dir4::Left));
+        assert!(!set.insert(99, Dir4::Left));
+        assert!(set.insert(99, Dir4::Up));
+        set.clear();
+        assert!(set.insert(99, Dir4::Left));
+    }
+
+    #[test]
+    fn test_loop_obstacles() {
+        let expected = [(6, 3), (7, 6), (7, 7), (8, 1), (8, 3), (9, 7)].map(Point::from);
+        assert_eq!(loop_obstacles(&example()), expected);
+        assert_eq!(loop_obstacles_serial(&example()), expected);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_serial() {
+        for name in ["example", "example2", "testdata"] {
+            let grid = Day06.parse(&Day06::INPUTS.read(name).unwrap()).unwrap();
+            assert_eq!(loop_obstacles_parallel(&grid), loop_obstacles_serial(&grid), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,564 @@
+use common::{
+    day_inputs,
+    geom::{Compass, Dir4, DirSet, Point},
+    grid::Grid,
+    inputs::DayInputs,
+    render::{Canvas, Color},
+    Error, Part, Solution,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    str::FromStr,
+};
+
+mod jump;
+mod replay;
+mod sim;
+#[cfg(feature = "parallel")]
+pub use jump::loop_obstacles_parallel;
+pub use jump::{loop_obstacles_serial, JumpTable, StateSet};
+pub use replay::{Event, Player, Replay, Step};
+pub use sim::{Guard, Outcome, Simulation, TurnRule};
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    const DAY: u8 = 6;
+    const INPUTS: DayInputs = day_inputs!("example", "example2", "testdata");
+    type Parsed = CharGrid;
+    type Answer = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        let grid = CharGrid::parse_map(input)?;
+        if let Some(&(second, _)) = grid.guards.get(1) {
+            let message = format!("the puzzle has one guard, found {}, see Simulation for more", grid.guards.len());
+            return Err(cell_error(input, second, message));
+        }
+        if let Some(cell) = grid.one_way.keys().chain(grid.portals.keys()).min_by_key(|cell| (cell.row, cell.col)) {
+            return Err(cell_error(input, *cell, "one-way cells and portals aren't part of the puzzle, see Simulation"));
+        }
+        Ok(grid)
+    }
+
+    fn part1(&self, grid: &Self::Parsed) -> Self::Answer {
+        let mut walker = Walker::new(grid);
+        walker.walk_to_end();
+        walker.cells_visited()
+    }
+
+    fn part2(&self, grid: &Self::Parsed) -> Self::Answer {
+        grid.loop_obstacles().len()
+    }
+
+    fn canvas(&self, grid: &Self::Parsed, part: Part) -> Option<Canvas> {
+        let mut walker = Walker::new(grid);
+        walker.walk_to_end();
+        let mut canvas = walker.get_canvas();
+        if part == Part::Two {
+            let obstacles = grid.loop_obstacles();
+            for obstacle in obstacles.iter() {
+                canvas.mark_glyph(*obstacle, LOOP_OBSTACLE_COLOR, 'O');
+            }
+            canvas.add_legend(LOOP_OBSTACLE_COLOR, format!("O {} obstacles that make the guard loop", obstacles.len()));
+        }
+        Some(canvas)
+    }
+}
+
+const OBSTACLE: &char = &'#';
+const START_COLOR: Color = Color::BrightYellow;
+const LOOP_OBSTACLE_COLOR: Color = Color::BrightRed;
+const PATH_COLOR: Color = Color::BrightBlue;
+const CROSSING_COLOR: Color = Color::BrightMagenta;
+
+#[derive(Debug)]
+pub struct Walker<'a> {
+    char_grid: &'a CharGrid,
+    guard_current_position: Point,
+    direction: Dir4,
+    /// An obstacle on top of the map's own, so a grid can be shared between walks.
+    extra_obstacle: Option<Point>,
+    turn: TurnRule,
+    /// The headings the guard had on each cell, after walking onto it or turning on it.
+    visited: Grid<DirSet<Dir4>>,
+    cells_visited: usize,
+    /// Every state of the walk in order: the start, then one per move or turn.
+    states: Vec<(Point, Dir4)>,
+    cycle: Option<Cycle>,
+}
+
+/// The part of a walk that repeats forever: from step `start` on, the guard is back on
+/// `position` heading `direction` every `length` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+    pub position: Point,
+    pub direction: Dir4,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(char_grid: &'a CharGrid) -> Self {
+        Self::for_guard(char_grid, char_grid.guard, char_grid.direction)
+    }
+
+    /// A walk of the guard that starts on `position` heading `direction`, for maps with more
+    /// than one.
+    pub fn for_guard(char_grid: &'a CharGrid, position: Point, direction: Dir4) -> Self {
+        let mut walker = Walker {
+            char_grid,
+            guard_current_position: position,
+            direction,
+            extra_obstacle: None,
+            turn: TurnRule::default(),
+            visited: Grid::new(char_grid.grid.rows(), char_grid.grid.cols(), DirSet::empty()),
+            cells_visited: 0,
+            states: Vec::new(),
+            cycle: None,
+        };
+        walker.add_visited_position();
+        walker
+    }
+
+    /// A walk on `char_grid` with one more obstacle at `obstacle`.
+    pub fn with_obstacle(char_grid: &'a CharGrid, obstacle: Point) -> Self {
+        let mut walker = Self::new(char_grid);
+        walker.extra_obstacle = Some(obstacle);
+        walker
+    }
+
+    pub fn with_turn(mut self, turn: TurnRule) -> Self {
+        self.turn = turn;
+        self
+    }
+
+    /// One move or one turn, see [`sim::next`].
+    pub fn walk(&mut self) -> Position {
+        let Some((position, direction)) = sim::next(self.char_grid, self.turn, self.extra_obstacle, self.guard_current_position, self.direction) else {
+            return Position::OffGrid;
+        };
+        (self.guard_current_position, self.direction) = (position, direction);
+        if self.add_visited_position() == NewVisitedPositionResult::LoopDetected {
+            return Position::LoopStart;
+        }
+        Position::OnGrid
+    }
+
+    /// Walks until the guard leaves the map or starts a loop.
+    pub fn walk_to_end(&mut self) -> Position {
+        let mut position = self.walk();
+        while position == Position::OnGrid {
+            position = self.walk();
+        }
+        position
+    }
+
+    /// Distinct cells the guard stood on.
+    pub fn cells_visited(&self) -> usize {
+        self.cells_visited
+    }
+
+    /// The loop the guard got caught in, once [`Walker::walk`] reported [`Position::LoopStart`].
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    fn add_visited_position(&mut self) -> NewVisitedPositionResult {
+        let state = (self.guard_current_position, self.direction);
+        let headings = &mut self.visited[state.0];
+        if headings.is_empty() {
+            self.cells_visited += 1;
+        }
+        let mut result = NewVisitedPositionResult::Ok;
+        if !headings.insert(state.1) && self.cycle.is_none() {
+            let start = self.states.iter().position(|&seen| seen == state).unwrap_or_default();
+            self.cycle = Some(Cycle {
+                start,
+                length: self.states.len() - start,
+                position: state.0,
+                direction: state.1,
+            });
+            result = NewVisitedPositionResult::LoopDetected;
+        }
+        self.states.push(state);
+        result
+    }
+
+    /// The map with the path drawn over it, see [`path_glyph`].
+    pub fn get_result_grid(&self) -> ResultGrid {
+        let mut grid = self.char_grid.grid.clone();
+        for (position, headings) in self.visited_cells() {
+            grid[position] = path_glyph(headings);
+        }
+        grid[self.states[0].0] = 'S';
+        if let Some(obstacle) = self.extra_obstacle {
+            grid[obstacle] = 'O';
+        }
+
+        ResultGrid::new(grid)
+    }
+
+    /// The map with the guard's path drawn over it, coloured by the direction the guard
+    /// walked each cell in.
+    pub fn get_canvas(&self) -> Canvas {
+        let mut canvas = Canvas::new(self.char_grid.grid.clone());
+        for (position, headings) in self.visited_cells() {
+            let color = match headings.iter().next() {
+                Some(direction) if headings.len() == 1 => Color::nth(direction.index()),
+                _ => CROSSING_COLOR,
+            };
+            canvas.mark_glyph(position, color, path_glyph(headings));
+        }
+        canvas.mark_glyph(self.states[0].0, START_COLOR, 'S');
+        if let Some(obstacle) = self.extra_obstacle {
+            canvas.mark_glyph(obstacle, LOOP_OBSTACLE_COLOR, 'O');
+        }
+        let mut path: Vec<Point> = self.states.iter().map(|&(position, _)| position).collect();
+        path.dedup();
+        canvas.add_polyline(path, PATH_COLOR);
+        canvas.add_legend(START_COLOR, "S start");
+        for direction in Dir4::all() {
+            canvas.add_legend(Color::nth(direction.index()), format!("{} walking {:?}", direction, direction).to_lowercase());
+        }
+        canvas.add_legend(CROSSING_COLOR, "+ | - walked in more than one direction");
+        canvas
+    }
+
+    fn visited_cells(&self) -> impl Iterator<Item = (Point, DirSet<Dir4>)> + '_ {
+        self.visited.iter().filter(|(_, headings)| !headings.is_empty()).map(|(coord, headings)| (Point::from(coord), *headings))
+    }
+}
+
+/// How a cell of the path is drawn: the heading if the guard only ever had one there, `+` where
+/// it turned or crossed its own path, `|` or `-` where it walked both ways along the same line.
+pub fn path_glyph(headings: DirSet<Dir4>) -> char {
+    let vertical = headings.contains(Dir4::Up) || headings.contains(Dir4::Down);
+    let horizontal = headings.contains(Dir4::Left) || headings.contains(Dir4::Right);
+    match headings.iter().next() {
+        Some(direction) if headings.len() == 1 => direction.symbol(),
+        _ if vertical && horizontal => '+',
+        _ if vertical => '|',
+        _ => '-',
+    }
+}
+
+/// A syntax error pointing at the cell `point` of the map `input`.
+fn cell_error(input: &str, point: Point, message: impl Into<String>) -> Error {
+    let line = input.lines().nth(point.row as usize).unwrap_or_default();
+    let cell = line.char_indices().nth(point.col as usize).map_or(line, |(i, c)| &line[i..i + c.len_utf8()]);
+    Error::syntax(line, cell, message).at_line(point.row as usize + 1)
+}
+
+#[derive(Debug, PartialEq)]
+enum NewVisitedPositionResult {
+    Ok,
+    LoopDetected,
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct CharGrid {
+    grid: Grid<char>,
+    obstacles: HashSet<Point>,
+    guard: Point,
+    /// The way the guard faces at the start.
+    direction: Dir4,
+    /// Every guard on the map, one of `^>v<`, in reading order. The first one is `guard`.
+    guards: Vec<(Point, Dir4)>,
+    /// Cells that can only be entered heading the way of their arrow, one of `↑→↓←`.
+    one_way: HashMap<Point, Dir4>,
+    /// Each portal cell and the one with the same digit it leads to.
+    portals: HashMap<Point, Point>,
+}
+
+impl CharGrid {
+    /// A map with any number of guards, one-way cells and portals, but at least one guard.
+    pub fn parse_map(input: &str) -> Result<Self, Error> {
+        let mut grid = CharGrid::from_str(input)?;
+        grid.get_objects();
+        if grid.guards.is_empty() {
+            let first = input.lines().next().unwrap_or_default();
+            return Err(Error::syntax(first, first, "no guard (one of ^>v<) found on the map").at_line(1));
+        }
+        let unpaired = grid.grid.find_all(|c| c.is_ascii_digit()).map(Point::from).find(|portal| !grid.portals.contains_key(portal));
+        if let Some(unpaired) = unpaired {
+            return Err(cell_error(input, unpaired, format!("portal '{}' needs exactly two cells", grid.at(&unpaired))));
+        }
+        Ok(grid)
+    }
+
+    pub fn get_objects(&mut self) {
+        self.obstacles = self.grid.find_all(|c| c == OBSTACLE).map(Point::from).collect();
+        self.guards = self
+            .grid
+            .iter()
+            .filter_map(|(coord, c)| Dir4::all().find(|dir| dir.symbol() == *c).map(|dir| (Point::from(coord), dir)))
+            .collect();
+        if let Some(&(guard, direction)) = self.guards.first() {
+            self.guard = guard;
+            self.direction = direction;
+        }
+        self.one_way = self
+            .grid
+            .iter()
+            .filter_map(|(coord, c)| Dir4::all().find(|dir| dir.arrow() == *c).map(|dir| (Point::from(coord), dir)))
+            .collect();
+        let mut portals: HashMap<char, Vec<Point>> = HashMap::new();
+        for (coord, c) in self.grid.iter().filter(|(_, c)| c.is_ascii_digit()) {
+            portals.entry(*c).or_default().push(Point::from(coord));
+        }
+        self.portals = portals
+            .into_values()
+            .filter_map(|cells| match cells[..] {
+                [a, b] => Some([(a, b), (b, a)]),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+    }
+
+    /// The guards and the way each of them faces at the start.
+    pub fn guards(&self) -> &[(Point, Dir4)] {
+        &self.guards
+    }
+
+    /// Free cells where an extra obstacle makes the guard walk in a loop, in reading order.
+    /// Only for maps without one-way cells and portals, see [`JumpTable`].
+    pub fn loop_obstacles(&self) -> Vec<Point> {
+        jump::loop_obstacles(self)
+    }
+
+    fn is_obstacle(&self, coord: &Point) -> bool {
+        self.obstacles.contains(coord)
+    }
+
+    fn at(&self, coord: &Point) -> char {
+        self.grid[*coord]
+    }
+
+    fn set(&mut self, coord: &Point, c: char) {
+        self.grid[*coord] = c;
+    }
+}
+
+impl FromStr for CharGrid {
+    type Err = Error;
+
+    fn from_str(data: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            grid: Grid::from_str(data)?,
+            ..Default::default()
+        })
+    }
+}
+
+impl Display for CharGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Position {
+    OnGrid,
+    OffGrid,
+    LoopStart,
+}
+
+pub struct ResultGrid {
+    grid: Grid<char>,
+}
+
+impl ResultGrid {
+    fn new(grid: Grid<char>) -> Self {
+        Self { grid }
+    }
+}
+
+impl Display for ResultGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use common::render::RenderOptions;
+
+    #[test]
+    fn test_frominput() {
+        let data = Day06::INPUTS.read("example").unwrap();
+        let mut grid = CharGrid::from_str(&data).unwrap();
+        grid.get_objects();
+        assert_eq!(grid.guard, Point::new(6, 4));
+        assert_eq!(grid.obstacles.len(), 8);
+        assert!(grid.obstacles.contains(&Point::new(8, 0)));
+    }
+
+    /// Part 2 the slow way: an obstacle on every free cell in turn and a full walk for each.
+    fn brute_force_loop_obstacles(grid: &CharGrid) -> Vec<Point> {
+        let mut loop_obstacles = Vec::new();
+        for coord in grid.grid.bounds().points() {
+            let c = grid.at(&coord);
+            if c != *OBSTACLE && coord != grid.guard && Walker::with_obstacle(grid, coord).walk_to_end() == Position::LoopStart {
+                loop_obstacles.push(coord);
+            }
+        }
+        loop_obstacles
+    }
+
+    #[test]
+    fn test_loop_obstacles() {
+        for name in ["example", "example2"] {
+            let grid = Day06.parse(&Day06::INPUTS.read(name).unwrap()).unwrap();
+            assert_eq!(grid.loop_obstacles(), brute_force_loop_obstacles(&grid), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = Day06.parse("..#.\n^..<\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:4: the puzzle has one guard, found 2, see Simulation for more ('<')");
+        let err = Day06.parse("....\n^.→1\n...1\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:3: one-way cells and portals aren't part of the puzzle, see Simulation ('→')");
+        let err = Day06.parse("..\n^1\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:2: portal '1' needs exactly two cells");
+        let err = Day06.parse("..#\n...\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:1:1: no guard (one of ^>v<) found on the map ('..#')");
+    }
+
+    #[test]
+    fn test_canvas() {
+        let grid = Day06.parse(&Day06::INPUTS.read("example").unwrap()).unwrap();
+        let mut walker = Walker::new(&grid);
+        walker.walk_to_end();
+        let canvas = walker.get_canvas();
+        assert_eq!(canvas.get_mark(Point::new(6, 4)).unwrap().glyph, Some('S'));
+        assert_eq!(canvas.get_mark(Point::new(2, 4)).unwrap().glyph, Some('^'));
+        assert_eq!(canvas.get_mark(Point::new(1, 4)).unwrap().glyph, Some('+'));
+        assert_eq!(canvas.get_mark(Point::new(1, 4)).unwrap().color, CROSSING_COLOR);
+        assert_eq!(canvas.get_mark(Point::new(0, 0)), None);
+        assert_eq!(canvas.polylines()[0].points[..3], [Point::new(6, 4), Point::new(1, 4), Point::new(1, 8)]);
+
+        let canvas = Solution::canvas(&Day06, &grid, Part::Two).unwrap();
+        assert_eq!(canvas.render(&RenderOptions::default().with_legend(false)).matches('O').count(), 6);
+        let out = canvas.render(&RenderOptions::default());
+        assert!(out.ends_with("bright red: O 6 obstacles that make the guard loop\n"));
+        assert_eq!(grid.loop_obstacles()[0], Point::new(6, 3));
+    }
+
+    #[test]
+    fn test_cycle() {
+        let grid = Day06.parse(&Day06::INPUTS.read("example").unwrap()).unwrap();
+        let mut walker = Walker::new(&grid);
+        assert_eq!(walker.walk_to_end(), Position::OffGrid);
+        assert_eq!(walker.cells_visited(), 41);
+        assert_eq!(walker.cycle(), None);
+
+        let mut walker = Walker::with_obstacle(&grid, Point::new(6, 3));
+        assert_eq!(walker.walk_to_end(), Position::LoopStart);
+        let cycle = walker.cycle().unwrap();
+        assert_eq!(cycle, Cycle { start: 0, length: 22, position: Point::new(6, 4), direction: Dir4::Up });
+        let result = walker.get_result_grid().to_string();
+        assert_eq!(result.lines().nth(1), Some("....+>>>+#"));
+        assert_eq!(result.lines().nth(6), Some(".#.OS<<<+."));
+
+        let grid = Day06.parse(&Day06::INPUTS.read("example2").unwrap()).unwrap();
+        let mut walker = Walker::new(&grid);
+        assert_eq!(walker.walk_to_end(), Position::LoopStart);
+        assert!(walker.cycle().unwrap().length > 0);
+    }
+
+    #[test]
+    fn test_path_glyph() {
+        assert_eq!(path_glyph(DirSet::from_iter([Dir4::Left])), '<');
+        assert_eq!(path_glyph(DirSet::from_iter([Dir4::Up, Dir4::Down])), '|');
+        assert_eq!(path_glyph(DirSet::from_iter([Dir4::Left, Dir4::Right])), '-');
+        assert_eq!(path_glyph(DirSet::from_iter([Dir4::Up, Dir4::Right])), '+');
+    }
+
+    /// A tiny xorshift generator, so the property tests below are random but repeatable.
+    struct Rng(u64);
+
+    impl Rng {
+        fn below(&mut self, n: usize) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            (self.0 % n as u64) as usize
+        }
+    }
+
+    fn random_grid(rng: &mut Rng) -> CharGrid {
+        let (rows, cols) = (1 + rng.below(9), 1 + rng.below(9));
+        let guard = (rng.below(rows), rng.below(cols));
+        let mut data = String::new();
+        for row in 0..rows {
+            for col in 0..cols {
+                data.push(match (row, col) {
+                    _ if (row, col) == guard => Dir4::ALL[rng.below(4)].symbol(),
+                    _ if rng.below(4) == 0 => *OBSTACLE,
+                    _ => '.',
+                });
+            }
+            data.push('\n');
+        }
+        Day06.parse(&data).unwrap()
+    }
+
+    type State = (Point, Dir4);
+
+    /// The walk the naive way: every state in a list, run until the guard is off the map or
+    /// has taken more steps than there are states, then look for the first repeat.
+    fn simulate(grid: &CharGrid) -> (Vec<State>, Option<(usize, usize)>) {
+        let (mut position, mut direction) = (grid.guard, grid.direction);
+        let mut states = vec![(position, direction)];
+        while states.len() <= 4 * grid.grid.rows() * grid.grid.cols() + 1 {
+            let next = position + direction.delta();
+            if grid.grid.get_at(next).is_none() {
+                return (states, None);
+            }
+            if grid.at(&next) == *OBSTACLE {
+                direction = direction.turn_right();
+            } else {
+                position = next;
+            }
+            states.push((position, direction));
+        }
+        for end in 1..states.len() {
+            if let Some(start) = states[..end].iter().position(|&state| state == states[end]) {
+                return (states[..end].to_vec(), Some((start, end - start)));
+            }
+        }
+        unreachable!("a walk longer than the number of states repeats one")
+    }
+
+    #[test]
+    fn test_walker_matches_simulation() {
+        let mut rng = Rng(0x5eed_0006);
+        for _ in 0..2000 {
+            let grid = random_grid(&mut rng);
+            let (states, cycle) = simulate(&grid);
+            let mut walker = Walker::new(&grid);
+            let end = walker.walk_to_end();
+            match Simulation::new(&grid).run()[..] {
+                [Outcome::Loops(cycle)] => assert_eq!(walker.cycle(), Some(cycle), "{}", grid),
+                [Outcome::Exits { steps, .. }] => assert_eq!(steps, states.len(), "{}", grid),
+                ref outcomes => panic!("{:?} for one guard on {}", outcomes, grid),
+            }
+            assert_eq!(end == Position::LoopStart, cycle.is_some(), "{}", grid);
+            assert_eq!(walker.cycle().map(|cycle| (cycle.start, cycle.length)), cycle, "{}", grid);
+
+            let cells: HashSet<Point> = states.iter().map(|&(position, _)| position).collect();
+            assert_eq!(walker.cells_visited(), cells.len(), "{}", grid);
+            let result = walker.get_result_grid();
+            for cell in cells {
+                let headings: DirSet<Dir4> = states.iter().filter(|state| state.0 == cell).map(|state| state.1).collect();
+                let expected = if cell == grid.guard { 'S' } else { path_glyph(headings) };
+                assert_eq!(result.grid[cell], expected, "{} at {}", grid, cell);
+            }
+        }
+    }
+}
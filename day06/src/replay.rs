@@ -0,0 +1,197 @@
+use common::{
+    geom::{Compass, Dir4, Point},
+    render::{Canvas, Color},
+    Error,
+};
+
+use crate::{CharGrid, Cycle, Position, Walker, LOOP_OBSTACLE_COLOR, OBSTACLE, START_COLOR};
+
+/// What happened in one step of the guard's walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Start,
+    Move,
+    /// Turned right in front of an obstacle, without moving.
+    Turn,
+    /// Walked off the map.
+    Exit,
+    /// Back on a cell heading the way it did there before, see [`Replay::cycle`].
+    Loop,
+}
+
+/// The guard after a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub position: Point,
+    pub direction: Dir4,
+    pub event: Event,
+}
+
+/// Every step of a walk, recorded to be played back or exported.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    grid: CharGrid,
+    obstacle: Option<Point>,
+    pub steps: Vec<Step>,
+    cycle: Option<Cycle>,
+}
+
+impl Replay {
+    /// Walks the guard until it leaves the map or loops, with an extra obstacle at `obstacle`.
+    pub fn record(grid: &CharGrid, obstacle: Option<Point>) -> Result<Self, Error> {
+        let mut grid = grid.clone();
+        if let Some(obstacle) = obstacle {
+            match grid.grid.get_at(obstacle) {
+                Some(c) if c != OBSTACLE && obstacle != grid.guard => {
+                    grid.set(&obstacle, *OBSTACLE);
+                    grid.obstacles.insert(obstacle);
+                }
+                _ => return Err(Error::GenericError(format!("Cannot place an obstacle at {}, the cell isn't free", obstacle))),
+            }
+        }
+
+        let mut walker = Walker::new(&grid);
+        let mut steps = vec![Step {
+            position: walker.guard_current_position,
+            direction: walker.direction,
+            event: Event::Start,
+        }];
+        loop {
+            let before = walker.guard_current_position;
+            let event = match walker.walk() {
+                Position::OffGrid => Event::Exit,
+                Position::LoopStart => Event::Loop,
+                Position::OnGrid if walker.guard_current_position == before => Event::Turn,
+                Position::OnGrid => Event::Move,
+            };
+            steps.push(Step {
+                position: walker.guard_current_position,
+                direction: walker.direction,
+                event,
+            });
+            if event == Event::Exit || event == Event::Loop {
+                break;
+            }
+        }
+        let cycle = walker.cycle();
+        Ok(Replay { grid, obstacle, steps, cycle })
+    }
+
+    /// [`Event::Exit`] or [`Event::Loop`].
+    pub fn outcome(&self) -> Event {
+        self.steps.last().map(|step| step.event).unwrap_or(Event::Start)
+    }
+
+    /// The loop the walk ended in, with `start` counted in [`Replay::steps`].
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    pub fn player(&self) -> Player<'_> {
+        let mut canvas = Canvas::new(self.grid.grid.clone());
+        if let Some(obstacle) = self.obstacle {
+            canvas.mark_glyph(obstacle, LOOP_OBSTACLE_COLOR, 'O');
+        }
+        let start = self.steps[0];
+        canvas.mark_glyph(start.position, START_COLOR, start.direction.symbol());
+        Player { replay: self, canvas, next: 1 }
+    }
+}
+
+/// Plays a [`Replay`] back on a [`Canvas`]: the cells the guard left show the direction it left
+/// them in, the guard's cell shows where it is heading.
+pub struct Player<'a> {
+    replay: &'a Replay,
+    canvas: Canvas,
+    next: usize,
+}
+
+impl Player<'_> {
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    /// Steps played so far, not counting the start.
+    pub fn steps_done(&self) -> usize {
+        self.next - 1
+    }
+
+    pub fn steps_total(&self) -> usize {
+        self.replay.steps.len() - 1
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.next >= self.replay.steps.len()
+    }
+
+    /// Plays the next step, returns the cells that changed or `None` at the end of the walk.
+    pub fn step(&mut self) -> Option<Vec<Point>> {
+        let before = *self.replay.steps.get(self.next - 1)?;
+        let step = *self.replay.steps.get(self.next)?;
+        self.next += 1;
+
+        let trail = |canvas: &mut Canvas| canvas.mark_glyph(before.position, Color::nth(before.direction.index()), before.direction.symbol());
+        match step.event {
+            Event::Start => Some(Vec::new()),
+            Event::Turn => {
+                self.canvas.mark_glyph(step.position, START_COLOR, step.direction.symbol());
+                Some(vec![step.position])
+            }
+            Event::Move | Event::Loop => {
+                trail(&mut self.canvas);
+                let color = if step.event == Event::Loop { LOOP_OBSTACLE_COLOR } else { START_COLOR };
+                self.canvas.mark_glyph(step.position, color, step.direction.symbol());
+                Some(vec![before.position, step.position])
+            }
+            Event::Exit => {
+                trail(&mut self.canvas);
+                Some(vec![before.position])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day06;
+    use common::{render::RenderOptions, Solution};
+
+    fn example() -> CharGrid {
+        Day06.parse(&Day06::INPUTS.read("example").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_record() {
+        let replay = Replay::record(&example(), None).unwrap();
+        assert_eq!(replay.outcome(), Event::Exit);
+        assert_eq!(replay.steps[0].position, Point::new(6, 4));
+        assert_eq!(replay.steps[5].event, Event::Move);
+        assert_eq!(replay.steps[6].event, Event::Turn);
+        assert_eq!(replay.steps[6].direction, Dir4::Right);
+        let moves = replay.steps.iter().filter(|step| step.event == Event::Move).count();
+        let turns = replay.steps.iter().filter(|step| step.event == Event::Turn).count();
+        assert_eq!((moves, turns), (44, 10));
+
+        let replay = Replay::record(&example(), Some(Point::new(6, 3))).unwrap();
+        assert_eq!(replay.outcome(), Event::Loop);
+        let cycle = replay.cycle().unwrap();
+        assert_eq!((cycle.start, cycle.length), (0, 22));
+        assert_eq!(replay.steps[cycle.start + cycle.length].position, replay.steps[cycle.start].position);
+        assert!(Replay::record(&example(), Some(Point::new(0, 4))).is_err());
+    }
+
+    #[test]
+    fn test_player() {
+        let replay = Replay::record(&example(), None).unwrap();
+        let mut player = replay.player();
+        let options = RenderOptions::default().with_legend(false);
+        assert_eq!(player.canvas().render(&options).lines().nth(6), Some(".#..^....."));
+        assert_eq!(player.step(), Some(vec![Point::new(6, 4), Point::new(5, 4)]));
+        assert_eq!(player.canvas().render(&options).lines().nth(5), Some("....^....."));
+        while player.step().is_some() {}
+        assert!(player.is_done());
+        assert_eq!(player.steps_done(), player.steps_total());
+        assert_eq!(player.canvas().render(&options).lines().last(), Some("......#v.."));
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::HashMap, str::FromStr};
+
+use common::{
+    geom::{Compass, Dir4, Point},
+    Error,
+};
+
+use crate::{CharGrid, Cycle, Position, Walker};
+
+/// Which way a guard turns in front of an obstacle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TurnRule {
+    #[default]
+    Right,
+    Left,
+    Reverse,
+}
+
+impl TurnRule {
+    pub fn apply(self, direction: Dir4) -> Dir4 {
+        match self {
+            TurnRule::Right => direction.turn_right(),
+            TurnRule::Left => direction.turn_left(),
+            TurnRule::Reverse => direction.opposite(),
+        }
+    }
+}
+
+impl FromStr for TurnRule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "right" => Ok(TurnRule::Right),
+            "left" => Ok(TurnRule::Left),
+            "reverse" => Ok(TurnRule::Reverse),
+            _ => Err(Error::syntax(s, s, "expected right, left or reverse")),
+        }
+    }
+}
+
+/// How a guard's walk ended, with steps counted from the start of the simulation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// Walked off the map from `position`.
+    Exits { steps: usize, position: Point },
+    /// Goes round its own loop forever without meeting a guard still walking.
+    Loops(Cycle),
+    /// Ended up on the same cell as guard `with`, or swapped cells with it.
+    Collides { steps: usize, with: usize, position: Point },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guard {
+    pub position: Point,
+    pub direction: Dir4,
+    /// `None` while the guard is walking.
+    pub outcome: Option<Outcome>,
+}
+
+/// All guards of a map walking in lockstep: each step every guard still walking moves or turns
+/// once, as a [`Walker`] of its own. Besides obstacles, one-way cells block guards heading any
+/// other way than their arrow and a portal takes a guard to the other cell with the same digit,
+/// keeping its heading.
+#[derive(Debug)]
+pub struct Simulation<'a> {
+    walkers: Vec<Walker<'a>>,
+    guards: Vec<Guard>,
+    steps: usize,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(grid: &'a CharGrid) -> Self {
+        let guards = grid
+            .guards()
+            .iter()
+            .map(|&(position, direction)| Guard {
+                position,
+                direction,
+                outcome: None,
+            })
+            .collect();
+        Simulation {
+            walkers: grid.guards().iter().map(|&(position, direction)| Walker::for_guard(grid, position, direction)).collect(),
+            guards,
+            steps: 0,
+        }
+    }
+
+    pub fn with_turn(mut self, turn: TurnRule) -> Self {
+        self.walkers = self.walkers.into_iter().map(|walker| walker.with_turn(turn)).collect();
+        self
+    }
+
+    pub fn guards(&self) -> &[Guard] {
+        &self.guards
+    }
+
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Moves every guard still walking by one step, returns `false` once none is. Once each of
+    /// them is in its loop, the rest of the walk is worked out without stepping.
+    pub fn step(&mut self) -> bool {
+        let before: Vec<Point> = self.guards.iter().map(|guard| guard.position).collect();
+        self.steps += 1;
+        for (guard, walker) in self.guards.iter_mut().zip(self.walkers.iter_mut()).filter(|(guard, _)| guard.outcome.is_none()) {
+            match walker.walk() {
+                Position::OffGrid => {
+                    guard.outcome = Some(Outcome::Exits {
+                        steps: self.steps,
+                        position: guard.position,
+                    });
+                }
+                _ => (guard.position, guard.direction) = (walker.guard_current_position, walker.direction),
+            }
+        }
+
+        let walking: Vec<usize> = (0..self.guards.len()).filter(|i| self.guards[*i].outcome.is_none()).collect();
+        let mut collisions = Vec::new();
+        for &a in walking.iter() {
+            let with = walking.iter().copied().find(|&b| {
+                let (guard_a, guard_b) = (&self.guards[a], &self.guards[b]);
+                let swapped = guard_a.position == before[b] && guard_b.position == before[a];
+                a != b && (guard_a.position == guard_b.position || swapped)
+            });
+            if let Some(with) = with {
+                collisions.push((a, with));
+            }
+        }
+        for (a, with) in collisions {
+            let guard = &mut self.guards[a];
+            guard.outcome = Some(Outcome::Collides {
+                steps: self.steps,
+                with,
+                position: guard.position,
+            });
+        }
+
+        let walking: Vec<usize> = (0..self.guards.len()).filter(|i| self.guards[*i].outcome.is_none()).collect();
+        if walking.is_empty() {
+            return false;
+        }
+        if walking.iter().all(|&i| self.walkers[i].cycle().is_some()) {
+            self.settle(walking);
+            return false;
+        }
+        true
+    }
+
+    /// Steps until every guard exited, looped or collided, the outcomes in the order of [`CharGrid::guards`].
+    pub fn run(mut self) -> Vec<Outcome> {
+        while self.step() {}
+        self.guards.iter().filter_map(|guard| guard.outcome).collect()
+    }
+
+    /// Ends the walk of the `walking` guards, all of them in their loops. Two guards are back in
+    /// the same pair of states after the lcm of their lengths, so the first step they meet at
+    /// is the solution of two congruences, or they never do. The earliest meetings are played
+    /// out in order, as they stop the guards involved, and the guards left go round forever.
+    fn settle(&mut self, mut walking: Vec<usize>) {
+        let loops: HashMap<usize, Loop> = walking.iter().filter_map(|&i| Some((i, Loop::new(&self.walkers[i])?))).collect();
+        let mut meetings = HashMap::new();
+        for (n, &a) in walking.iter().enumerate() {
+            for &b in &walking[n + 1..] {
+                if let Some(step) = loops[&a].first_meeting(&loops[&b], self.steps) {
+                    meetings.insert((a, b), step);
+                    meetings.insert((b, a), step);
+                }
+            }
+        }
+
+        let meeting = |walking: &[usize], a: usize, b: usize| walking.contains(&a) && walking.contains(&b);
+        while let Some(step) = meetings.iter().filter(|((a, b), _)| meeting(&walking, *a, *b)).map(|(_, step)| *step).min() {
+            let collisions: Vec<(usize, usize)> = walking
+                .iter()
+                .filter_map(|&a| walking.iter().find(|&&b| meetings.get(&(a, b)) == Some(&step)).map(|&with| (a, with)))
+                .collect();
+            for (a, with) in collisions {
+                let guard = &mut self.guards[a];
+                (guard.position, guard.direction) = loops[&a].state(step);
+                guard.outcome = Some(Outcome::Collides {
+                    steps: step,
+                    with,
+                    position: guard.position,
+                });
+            }
+            walking.retain(|&i| self.guards[i].outcome.is_none());
+        }
+        for i in walking {
+            self.guards[i].outcome = self.walkers[i].cycle().map(Outcome::Loops);
+        }
+    }
+}
+
+/// The loop of a guard, with the steps it is on each cell and moves along each pair of cells,
+/// counted from the start of the loop.
+struct Loop {
+    start: usize,
+    states: Vec<(Point, Dir4)>,
+    cells: HashMap<Point, Vec<usize>>,
+    moves: HashMap<(Point, Point), Vec<usize>>,
+}
+
+impl Loop {
+    fn new(walker: &Walker) -> Option<Self> {
+        let cycle = walker.cycle()?;
+        let states = walker.states[cycle.start..cycle.start + cycle.length].to_vec();
+        let mut cells: HashMap<Point, Vec<usize>> = HashMap::new();
+        let mut moves: HashMap<(Point, Point), Vec<usize>> = HashMap::new();
+        for (phase, &(position, _)) in states.iter().enumerate() {
+            cells.entry(position).or_default().push(phase);
+            let (before, _) = states[(phase + states.len() - 1) % states.len()];
+            if before != position {
+                moves.entry((before, position)).or_default().push(phase);
+            }
+        }
+        Some(Loop {
+            start: cycle.start,
+            states,
+            cells,
+            moves,
+        })
+    }
+
+    /// The state at `step`, which is past the start of the loop.
+    fn state(&self, step: usize) -> (Point, Dir4) {
+        self.states[(step - self.start) % self.states.len()]
+    }
+
+    /// The first step after `after` this guard and `other` are on the same cell or swap cells,
+    /// both of them walking their loops until then.
+    fn first_meeting(&self, other: &Loop, after: usize) -> Option<usize> {
+        let same_cell = self.cells.iter().filter_map(|(cell, phases)| Some((phases, other.cells.get(cell)?)));
+        let swaps = self.moves.iter().filter_map(|((from, to), phases)| Some((phases, other.moves.get(&(*to, *from))?)));
+        same_cell
+            .chain(swaps)
+            .flat_map(|(phases, others)| phases.iter().flat_map(move |phase| others.iter().map(move |other_phase| (*phase, *other_phase))))
+            .filter_map(|(phase, other_phase)| {
+                first_common_step(
+                    (self.start + phase, self.states.len()),
+                    (other.start + other_phase, other.states.len()),
+                    after,
+                )
+            })
+            .min()
+    }
+}
+
+/// The first step after `after` that is `a.0` modulo `a.1` and `b.0` modulo `b.1`, by the
+/// Chinese remainder theorem for moduli that aren't coprime. `None` if there is none, or it
+/// or a step on the way doesn't fit, which takes loops longer than any map has.
+fn first_common_step(a: (usize, usize), b: (usize, usize), after: usize) -> Option<usize> {
+    let (a, m, b, n) = (a.0 as i128, a.1 as i128, b.0 as i128, b.1 as i128);
+    // Extended Euclid: m * x + n * y == gcd.
+    let (mut gcd, mut next_gcd, mut x, mut next_x) = (m, n, 1, 0);
+    while next_gcd != 0 {
+        let quotient = gcd / next_gcd;
+        (gcd, next_gcd) = (next_gcd, gcd - quotient * next_gcd);
+        (x, next_x) = (next_x, x - quotient * next_x);
+    }
+    if (b - a) % gcd != 0 {
+        return None;
+    }
+    let lcm = (m / gcd).checked_mul(n)?;
+    let solution = a + m.checked_mul(((b - a) / gcd).checked_mul(x)?.rem_euclid(n / gcd))?;
+    let first = after as i128 + 1;
+    usize::try_from(first + (solution - first).rem_euclid(lcm)).ok()
+}
+
+/// Where a guard on `position` heading `direction` is after one step, `None` if it walks off the
+/// map. `extra_obstacle` blocks like the obstacles of the map.
+pub(crate) fn next(grid: &CharGrid, turn: TurnRule, extra_obstacle: Option<Point>, position: Point, direction: Dir4) -> Option<(Point, Dir4)> {
+    let ahead = position + direction.delta();
+    if !grid.grid.bounds().contains(&ahead) {
+        return None;
+    }
+    let blocked = grid.is_obstacle(&ahead) || extra_obstacle == Some(ahead) || grid.one_way.get(&ahead).is_some_and(|way| *way != direction);
+    if blocked {
+        return Some((position, turn.apply(direction)));
+    }
+    Some((grid.portals.get(&ahead).copied().unwrap_or(ahead), direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(map: &str, turn: TurnRule) -> Vec<Outcome> {
+        Simulation::new(&CharGrid::parse_map(map).unwrap()).with_turn(turn).run()
+    }
+
+    #[test]
+    fn test_turn_rules() {
+        assert_eq!(TurnRule::Left.apply(Dir4::Up), Dir4::Left);
+        assert_eq!(TurnRule::Reverse.apply(Dir4::Right), Dir4::Left);
+        assert_eq!("reverse".parse::<TurnRule>().unwrap(), TurnRule::Reverse);
+        assert!("around".parse::<TurnRule>().is_err());
+
+        let map = "#...\n...#\n>..#\n";
+        assert_eq!(run(map, TurnRule::Right), [Outcome::Exits { steps: 4, position: Point::new(2, 2) }]);
+        assert_eq!(run(map, TurnRule::Left), [Outcome::Exits { steps: 6, position: Point::new(0, 2) }]);
+        let cycle = Cycle {
+            start: 0,
+            length: 6,
+            position: Point::new(0, 1),
+            direction: Dir4::Right,
+        };
+        assert_eq!(run("#>..#\n", TurnRule::Reverse), [Outcome::Loops(cycle)]);
+    }
+
+    #[test]
+    fn test_guards() {
+        assert_eq!(
+            run(">..<\n....\n", TurnRule::Right),
+            [
+                Outcome::Collides { steps: 2, with: 1, position: Point::new(0, 2) },
+                Outcome::Collides { steps: 2, with: 0, position: Point::new(0, 1) },
+            ]
+        );
+        assert_eq!(
+            run(">.<\n^..\n", TurnRule::Right),
+            [
+                Outcome::Collides { steps: 1, with: 1, position: Point::new(0, 1) },
+                Outcome::Collides { steps: 1, with: 0, position: Point::new(0, 1) },
+                Outcome::Exits { steps: 2, position: Point::new(0, 0) },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loops() {
+        // Loops of 6 and 4 steps on rows of their own, each reported with its own length.
+        let outcomes = run("#>..#\n#<.#.\n", TurnRule::Reverse);
+        let cycle = |length, position, direction| Outcome::Loops(Cycle { start: 0, length, position, direction });
+        assert_eq!(outcomes, [cycle(6, Point::new(0, 1), Dir4::Right), cycle(4, Point::new(1, 1), Dir4::Left)]);
+
+        // Guards going back and forth on rows of prime lengths are all back at their starts
+        // after billions of steps only.
+        let lengths = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+        let map: String = lengths.iter().map(|&length| format!("#>{}#{}\n", ".".repeat(length - 1), "#".repeat(37 - length))).collect();
+        let outcomes = run(&map, TurnRule::Reverse);
+        for (row, (outcome, length)) in outcomes.iter().zip(lengths).enumerate() {
+            assert_eq!(*outcome, cycle(2 * length, Point::new(row as i64, 1), Dir4::Right));
+        }
+    }
+
+    #[test]
+    fn test_meeting_in_loops() {
+        // Both guards are in their loops of 6 and 8 steps when they meet on the crossing.
+        let map = "...#..\n...v..\n#>...#\n......\n...#..\n";
+        assert_eq!(
+            run(map, TurnRule::Reverse),
+            [
+                Outcome::Collides { steps: 10, with: 1, position: Point::new(2, 3) },
+                Outcome::Collides { steps: 10, with: 0, position: Point::new(2, 3) },
+            ]
+        );
+        assert_eq!(first_common_step((2, 8), (4, 6), 8), Some(10));
+        assert_eq!(first_common_step((2, 8), (1, 6), 0), None);
+        assert_eq!(first_common_step((0, 7), (0, 11), 0), Some(77));
+        assert_eq!(first_common_step((1, 1 << 40), (0, (1 << 40) - 1), 0), None);
+        assert_eq!(first_common_step((1, usize::MAX), (0, usize::MAX - 1), 0), None);
+    }
+
+    #[test]
+    fn test_one_way_and_portals() {
+        assert_eq!(run(">.↓.\n", TurnRule::Right), [Outcome::Exits { steps: 3, position: Point::new(0, 1) }]);
+        assert_eq!(run(">.→.\n", TurnRule::Right), [Outcome::Exits { steps: 4, position: Point::new(0, 3) }]);
+        // The portal on the right takes the guard to the top left corner.
+        assert_eq!(run("1...\n....\n.>.1\n", TurnRule::Right), [Outcome::Exits { steps: 6, position: Point::new(0, 3) }]);
+        assert!(CharGrid::parse_map("^.1\n").is_err());
+        assert!(CharGrid::parse_map("...\n").is_err());
+    }
+}
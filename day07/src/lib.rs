@@ -0,0 +1,510 @@
+use common::{day_inputs, inputs::DayInputs, parse_token, Error, Solution};
+use std::{marker::PhantomData, ops::ControlFlow, str::FromStr};
+
+mod number;
+mod operator;
+mod semantics;
+pub use number::{Number, Total};
+pub use operator::{parse_operators, BinaryOperator, Inverse, Operator};
+pub use semantics::{parse_semantics, Semantics, Solved};
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    const DAY: u8 = 7;
+    const INPUTS: DayInputs = day_inputs!("example", "testdata");
+    type Parsed = Equations;
+    type Answer = Total;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        let mut equations = Equations::default();
+        for (i, line) in input.lines().enumerate() {
+            match Equation::from_str(line) {
+                Ok(equation) => equations.small.push(equation),
+                #[cfg(feature = "bigint")]
+                Err(e) => equations.big.push(Equation::from_str(line).map_err(|_| e.at_line(i + 1))?),
+                #[cfg(not(feature = "bigint"))]
+                Err(e) => return Err(e.at_line(i + 1)),
+            }
+        }
+        Ok(equations)
+    }
+
+    fn part1(&self, equations: &Self::Parsed) -> Self::Answer {
+        equations.total(&[Operator::Add, Operator::Mul])
+    }
+
+    fn part2(&self, equations: &Self::Parsed) -> Self::Answer {
+        equations.total(&[Operator::Add, Operator::Mul, Operator::Concat])
+    }
+}
+
+/// The equations of an input. Without the `bigint` feature a number that doesn't fit in `i64` is
+/// a syntax error, with it the equations that have one are solved with `BigInt` and the rest
+/// stay on the faster `i64`.
+#[derive(Debug, Default)]
+pub struct Equations {
+    pub small: Vec<Equation>,
+    #[cfg(feature = "bigint")]
+    pub big: Vec<Equation<num_bigint::BigInt>>,
+}
+
+impl Equations {
+    pub fn len(&self) -> usize {
+        #[cfg(feature = "bigint")]
+        return self.small.len() + self.big.len();
+        #[cfg(not(feature = "bigint"))]
+        self.small.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The sum of the results of the equations `operators` solve.
+    pub fn total(&self, operators: &[Operator]) -> Total {
+        let total = Equation::total(&self.small, operators);
+        #[cfg(feature = "bigint")]
+        let total = total + Equation::total(&self.big, operators);
+        total
+    }
+}
+
+#[derive(Debug)]
+pub struct Equation<N = i64> {
+    expected_result: N,
+    operands: Vec<N>,
+}
+
+impl<N: Number> Equation<N>
+where
+    Total: From<N>,
+{
+    /// The sum of the results of the `equations` `operators` solve.
+    pub fn total(equations: &[Self], operators: &[Operator]) -> Total {
+        equations
+            .iter()
+            .filter(|eq| eq.is_solvable(operators))
+            .map(|eq| Total::from(eq.expected_result.clone()))
+            .sum()
+    }
+}
+
+impl<N: Number> Equation<N> {
+    pub fn expected_result(&self) -> &N {
+        &self.expected_result
+    }
+
+    pub fn operands(&self) -> &[N] {
+        &self.operands
+    }
+
+    pub fn is_solvable<O: BinaryOperator<N> + Clone>(&self, operators: &[O]) -> bool {
+        self.first_solution(operators).is_some()
+    }
+
+    /// The first operators found that make the equation true, one between each two operands.
+    pub fn first_solution<O: BinaryOperator<N> + Clone>(&self, operators: &[O]) -> Option<Vec<O>> {
+        let mut first = None;
+        self.search(operators, &mut |solution| {
+            first = Some(solution.to_vec());
+            ControlFlow::Break(())
+        });
+        first
+    }
+
+    /// Every choice of operators that makes the equation true.
+    pub fn solutions<O: BinaryOperator<N> + Clone>(&self, operators: &[O]) -> Vec<Vec<O>> {
+        let mut solutions = Vec::new();
+        self.search(operators, &mut |solution| {
+            solutions.push(solution.to_vec());
+            ControlFlow::Continue(())
+        });
+        solutions
+    }
+
+    pub fn count_solutions<O: BinaryOperator<N> + Clone>(&self, operators: &[O]) -> usize {
+        let mut count = 0;
+        self.search(operators, &mut |_| {
+            count += 1;
+            ControlFlow::Continue(())
+        });
+        count
+    }
+
+    /// The first way found to solve the equation with its operators evaluated as `semantics` says.
+    pub fn first_solution_with<O: BinaryOperator<N> + Clone>(&self, operators: &[O], semantics: Semantics) -> Option<Solved<O>> {
+        let (operators, expression) = match semantics {
+            Semantics::LeftToRight => {
+                let solution = self.first_solution(operators)?;
+                let expression = self.expression(&solution);
+                (solution, expression)
+            }
+            Semantics::Precedence => {
+                let solution = semantics::first_with_precedence(self, operators)?;
+                let expression = self.expression(&solution);
+                (solution, expression)
+            }
+            Semantics::Parenthesised => semantics::first_parenthesised(self, operators)?,
+        };
+        Some(Solved {
+            semantics,
+            operators,
+            expression,
+        })
+    }
+
+    /// Tries each of `semantics` in turn, the first one that solves the equation wins.
+    pub fn solved_by<O: BinaryOperator<N> + Clone>(&self, operators: &[O], semantics: &[Semantics]) -> Option<Solved<O>> {
+        semantics.iter().find_map(|semantics| self.first_solution_with(operators, *semantics))
+    }
+
+    /// The equation with `operators` filled in, e.g. `81 + 40 * 27 = 3267`.
+    pub fn expression<O: BinaryOperator<N>>(&self, operators: &[O]) -> String {
+        let mut expression = self.operands[0].to_string();
+        for (operator, operand) in operators.iter().zip(&self.operands[1..]) {
+            expression += &format!(" {} {}", operator, operand);
+        }
+        format!("{} = {}", expression, self.expected_result)
+    }
+
+    /// Evaluates the operands left to right with the given operators, `None` if an operator
+    /// isn't defined for its operands.
+    pub fn solve<O: BinaryOperator<N>>(&self, operators: &[O]) -> Option<N> {
+        let mut result = self.operands[0].clone();
+        for (operator, operand) in operators.iter().zip(&self.operands[1..]) {
+            result = operator.apply(&result, operand)?;
+        }
+        Some(result)
+    }
+
+    fn search<O: BinaryOperator<N> + Clone>(&self, operators: &[O], found: &mut impl FnMut(&[O]) -> ControlFlow<()>) {
+        let mut search = Search {
+            operators,
+            non_negative: operators.iter().all(|operator| operator.keeps_non_negative()),
+            chosen: Vec::with_capacity(self.operands.len()),
+            found,
+            number: PhantomData,
+        };
+        let _ = search.backward(&self.expected_result, &self.operands);
+    }
+}
+
+/// A search for the operators that solve an equation.
+struct Search<'a, N, O, F> {
+    operators: &'a [O],
+    /// No operator makes a negative number out of the (never negative) operands.
+    non_negative: bool,
+    /// The operators picked by [`Search::backward`] so far, last one first.
+    chosen: Vec<O>,
+    found: &'a mut F,
+    number: PhantomData<N>,
+}
+
+impl<N: Number, O: BinaryOperator<N> + Clone, F: FnMut(&[O]) -> ControlFlow<()>> Search<'_, N, O, F> {
+    /// Works backwards from `target`: the last operand was combined with the result of the ones
+    /// before it, so undo every operator that could have produced `target` with it and recurse
+    /// on the rest. While no operator can turn the operands negative, a negative target means
+    /// the operands before it already overshot it.
+    fn backward(&mut self, target: &N, operands: &[N]) -> ControlFlow<()> {
+        if target.is_negative() && self.non_negative {
+            return ControlFlow::Continue(());
+        }
+        match operands {
+            [] => ControlFlow::Continue(()),
+            [first] if target == first => self.report(&[]),
+            [_] => ControlFlow::Continue(()),
+            [rest @ .., last] => {
+                for operator in self.operators {
+                    let before = match operator.invert(target, last) {
+                        Some(Inverse::Impossible) => continue,
+                        Some(Inverse::Left(left)) => left,
+                        Some(Inverse::Any) | None => {
+                            // No way back through this operator, or any value will do like in front
+                            // of a multiplication with zero: try the operands before it forwards, so
+                            // only values they actually have count.
+                            self.chosen.push(operator.clone());
+                            let flow = self.forward(rest[0].clone(), &rest[1..], &mut Vec::new(), &|value| {
+                                operator.apply_up_to(value, last, target).as_ref() == Some(target)
+                            });
+                            self.chosen.pop();
+                            flow?;
+                            continue;
+                        }
+                    };
+                    self.chosen.push(operator.clone());
+                    let flow = self.backward(&before, rest);
+                    self.chosen.pop();
+                    flow?;
+                }
+                ControlFlow::Continue(())
+            }
+        }
+    }
+
+    /// Tries every choice of operators for the `operands` after `value`, left to right, and
+    /// reports those that end in a value `fits` accepts. `prefix` holds the operators picked
+    /// so far, first one first.
+    fn forward(&mut self, value: N, operands: &[N], prefix: &mut Vec<O>, fits: &dyn Fn(&N) -> bool) -> ControlFlow<()> {
+        let Some((next, rest)) = operands.split_first() else {
+            return if fits(&value) { self.report(prefix) } else { ControlFlow::Continue(()) };
+        };
+        for operator in self.operators {
+            if let Some(value) = operator.apply(&value, next) {
+                prefix.push(operator.clone());
+                let flow = self.forward(value, rest, prefix, fits);
+                prefix.pop();
+                flow?;
+            }
+        }
+        ControlFlow::Continue(())
+    }
+
+    /// Hands the operators in front of the last [`Search::backward`] step, followed by the ones
+    /// picked backwards, to the caller.
+    fn report(&mut self, prefix: &[O]) -> ControlFlow<()> {
+        let solution: Vec<O> = prefix.iter().cloned().chain(self.chosen.iter().rev().cloned()).collect();
+        (self.found)(&solution)
+    }
+}
+
+impl<N: Number> FromStr for Equation<N> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((result_str, operands_str)) = s.split_once(":") else {
+            return Err(Error::syntax(s, s, "general form of equations is 'result: op1 op2 op3 ...'"));
+        };
+
+        let equation = Equation {
+            expected_result: parse_token(s, result_str)?,
+            operands: operands_str.trim().split(" ").map(|n| parse_token(s, n)).collect::<Result<_, _>>()?,
+        };
+        if let Some(negative) = std::iter::once(result_str.trim()).chain(operands_str.split_whitespace()).find(|n| n.starts_with('-')) {
+            return Err(Error::syntax(s, negative, "numbers can't be negative"));
+        }
+
+        Ok(equation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let eq1 = Equation::<i64>::from_str("190: 10 19").unwrap();
+        assert_eq!(eq1.solve(&[Operator::Add]), Some(29));
+        assert_eq!(eq1.solve(&[Operator::Mul]), Some(190));
+        assert_eq!(eq1.solve(&[Operator::Concat]), Some(1019));
+        let eq1 = Equation::<i64>::from_str("3267: 81 40 27").unwrap();
+        assert_eq!(eq1.solve(&[Operator::Add, Operator::Add]), Some(148));
+        assert_eq!(eq1.solve(&[Operator::Add, Operator::Mul]), Some((81 + 40) * 27));
+        assert_eq!(eq1.solve(&[Operator::Mul, Operator::Mul]), Some((81 * 40) * 27));
+        assert_eq!(eq1.solve(&[Operator::Concat, Operator::Add]), Some(8167));
+        assert_eq!(eq1.solve(&[Operator::Concat, Operator::Concat]), Some(814027));
+        assert_eq!(eq1.solve(&[Operator::Add, Operator::Concat]), Some(12127));
+
+        let eq1 = Equation::<i64>::from_str("7290: 6 8 6 15").unwrap();
+        assert_eq!(eq1.solve(&[Operator::Add, Operator::Concat, Operator::Mul]), Some(146 * 15));
+    }
+
+    #[test]
+    fn test_solvable() {
+        let eq1 = Equation::<i64>::from_str("3267: 81 40 27").unwrap();
+        assert!(eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("190: 10 19").unwrap();
+        assert!(eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("83: 17 5").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("156: 15 6").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("7290: 6 8 6 15").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("161011: 16 10 13").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("192: 17 8 14").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("21037: 9 7 18 13").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+        let eq1 = Equation::<i64>::from_str("292: 11 6 16 20").unwrap();
+        assert!(eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+
+        // Part 2
+        let eq1 = Equation::<i64>::from_str("7290: 6 8 6 15").unwrap();
+        assert!(eq1.is_solvable(&[Operator::Add, Operator::Mul, Operator::Concat]));
+        let eq1 = Equation::<i64>::from_str("161011: 16 10 13").unwrap();
+        assert!(!eq1.is_solvable(&[Operator::Add, Operator::Mul, Operator::Concat]));
+        let eq1 = Equation::<i64>::from_str("0: 7 3 0").unwrap();
+        assert!(eq1.is_solvable(&[Operator::Add, Operator::Mul]));
+    }
+
+    /// Every combination of `operators` between the operands, evaluated one by one.
+    fn brute_force_count(eq: &Equation, operators: &[Operator]) -> usize {
+        let slots = eq.operands.len() - 1;
+        (0..operators.len().pow(slots as u32))
+            .filter(|combination| {
+                let mut combination = *combination;
+                let chosen: Vec<Operator> = (0..slots)
+                    .map(|_| {
+                        let operator = operators[combination % operators.len()];
+                        combination /= operators.len();
+                        operator
+                    })
+                    .collect();
+                eq.solve(&chosen) == Some(eq.expected_result)
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_solvable_matches_brute_force() {
+        use Operator::*;
+
+        let data = Day07::INPUTS.read("testdata").unwrap();
+        let equations = Day07.parse(&data).unwrap();
+        for eq in equations.small.iter().filter(|eq| eq.operands.len() <= 7) {
+            for operators in [&[Add, Mul][..], &[Add, Mul, Concat]] {
+                assert_eq!(eq.is_solvable(operators), brute_force_count(eq, operators) > 0, "{:?}", eq);
+                for solution in eq.solutions(operators) {
+                    assert_eq!(eq.solve(&solution), Some(eq.expected_result), "{}", eq.expression(&solution));
+                }
+            }
+        }
+
+        // Operators without an inverse, ones that turn the operands negative and zero times
+        // anything, which only counts where the operands in front of it have a value.
+        let data = Day07::INPUTS.read("example").unwrap();
+        let mut equations = Day07.parse(&data).unwrap();
+        let extra = ["6: 3 9 2 4", "0: 5 5 7", "1: 12 3 4 8", "7: 2 3 1", "0: 5 0 0", "0: 9223372036854775807 9223372036854775807 0"];
+        equations.small.extend(extra.map(|line| Equation::<i64>::from_str(line).unwrap()));
+        for eq in equations.small.iter() {
+            for operators in [&[Add, Sub, Mul, Div][..], &[Add, Mul], &[Div, Mul], &[Xor, Min, Max, Pow, Concat2], &[Sub, Concat16, Mul, Max]] {
+                let count = brute_force_count(eq, operators);
+                assert_eq!(eq.count_solutions(operators), count, "{:?} with {:?}", eq, operators);
+                assert_eq!(eq.is_solvable(operators), count > 0, "{:?} with {:?}", eq, operators);
+            }
+        }
+    }
+
+    /// The remainder, to show that operators can come from outside the crate.
+    struct Rem;
+
+    impl std::fmt::Display for Rem {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "%")
+        }
+    }
+
+    impl BinaryOperator for Rem {
+        fn apply(&self, left: &i64, right: &i64) -> Option<i64> {
+            left.checked_rem(*right)
+        }
+    }
+
+    #[test]
+    fn test_custom_operator() {
+        let operators: [&dyn BinaryOperator; 2] = [&Operator::Add, &Rem];
+        let eq = Equation::<i64>::from_str("3: 7 5 2 3").unwrap();
+        let expressions: Vec<String> = eq.solutions(&operators).iter().map(|solution| eq.expression(solution)).collect();
+        assert_eq!(expressions, ["7 + 5 % 2 + 3 = 3", "7 % 5 % 2 + 3 = 3"]);
+    }
+
+    #[test]
+    fn test_solutions() {
+        let (add, mul, concat) = (Operator::Add, Operator::Mul, Operator::Concat);
+        let eq = Equation::<i64>::from_str("3267: 81 40 27").unwrap();
+        let solutions = eq.solutions(&[add, mul]);
+        assert_eq!(solutions.len(), 2);
+        let expressions: Vec<String> = solutions.iter().map(|solution| eq.expression(solution)).collect();
+        assert!(expressions.contains(&"81 + 40 * 27 = 3267".to_string()));
+        assert!(expressions.contains(&"81 * 40 + 27 = 3267".to_string()));
+
+        let eq = Equation::<i64>::from_str("7290: 6 8 6 15").unwrap();
+        assert_eq!(eq.first_solution(&[add, mul]), None);
+        let solution = eq.first_solution(&[add, mul, concat]).unwrap();
+        assert_eq!(eq.expression(&solution), "6 * 8 || 6 * 15 = 7290");
+        assert_eq!(eq.solve(&solution), Some(7290));
+
+        // Whatever comes before the zero works.
+        let eq = Equation::<i64>::from_str("0: 7 3 0").unwrap();
+        assert_eq!(eq.count_solutions(&[add, mul]), 2);
+        assert_eq!(eq.count_solutions(&[add, mul, concat]), 3);
+    }
+
+    #[test]
+    fn test_solveablefrominput() {
+        let data = Day07::INPUTS.read("example").unwrap();
+
+        let mut solveable_equations: Vec<usize> = vec![];
+        for (i, line) in data.lines().enumerate() {
+            if Equation::<i64>::from_str(line).unwrap().is_solvable(&[Operator::Add, Operator::Mul]) {
+                solveable_equations.push(i);
+            }
+        }
+
+        assert_eq!(solveable_equations, vec![0, 1, 8]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        use Operator::*;
+
+        // Overflowing is no match rather than a wrapped result that happens to fit.
+        let eq = Equation::<i64>::from_str("9223372036854775807: 9223372036854775807 1").unwrap();
+        assert_eq!(eq.solve(&[Add]), None);
+        assert_eq!(eq.count_solutions(&[Add, Mul, Concat]), 1);
+        let eq = Equation::<i64>::from_str("5: 4294967296 4294967296 5").unwrap();
+        assert!(!eq.is_solvable(&[Add, Mul, Concat, Pow]));
+        assert_eq!(eq.solve(&[Mul, Add]), None);
+
+        // Results that fit add up to a total that doesn't.
+        let equations = Day07.parse("9223372036854775807: 9223372036854775806 1\n9223372036854775807: 9223372036854775806 1").unwrap();
+        assert_eq!(Day07.part1(&equations).to_string(), "18446744073709551614");
+    }
+
+    #[cfg(not(feature = "bigint"))]
+    #[test]
+    fn test_too_large() {
+        let err = Day07.parse("190: 10 19\n18446744073709551616: 4294967296 4294967296").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: cannot parse '18446744073709551616': number too large to fit in target type");
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_bigint() {
+        let equations = Day07.parse("190: 10 19\n18446744073709551616: 4294967296 4294967296\n1234567890123456789012: 1234567890 123456789012").unwrap();
+        assert_eq!((equations.small.len(), equations.big.len()), (1, 2));
+        assert_eq!(Day07.part1(&equations).to_string(), "18446744073709551806");
+        assert_eq!(Day07.part2(&equations).to_string(), "1253014634197166340818");
+        let eq = &equations.big[1];
+        let solution = eq.first_solution(&[Operator::Add, Operator::Mul, Operator::Concat]).unwrap();
+        assert_eq!(eq.expression(&solution), "1234567890 || 123456789012 = 1234567890123456789012");
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = Day07.parse("190: 10 19\n3267 81 40 27").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: general form of equations is 'result: op1 op2 op3 ...' ('3267 81 40 27')");
+        let err = Day07.parse("190: 10 19\n3267: 81 4o 27").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:10: cannot parse '4o': invalid digit found in string");
+        let err = Day07.parse("190: 10 -19").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:1:9: numbers can't be negative ('-19')");
+    }
+
+    #[test]
+    fn test_frominput() {
+        let data = Day07::INPUTS.read("example").unwrap();
+        let lines: Vec<&str> = data.lines().collect();
+        let equation = Equation::<i64>::from_str(lines[0]).unwrap();
+        assert_eq!(equation.expected_result, 190);
+        assert_eq!(equation.operands, vec![10, 19]);
+
+        let equation = Equation::<i64>::from_str(lines[8]).unwrap();
+        assert_eq!(equation.expected_result, 292);
+        assert_eq!(equation.operands, vec![11, 6, 16, 20]);
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    fmt::{Debug, Display},
+    hash::Hash,
+    str::FromStr,
+};
+
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub, Signed, ToPrimitive};
+
+/// The integers an [`crate::Equation`] is made of: `i64`, and with the `bigint` feature `BigInt`
+/// for equations with numbers that don't fit. Operators on them use checked arithmetic, an
+/// overflow is no result rather than a wrong one.
+pub trait Number:
+    Signed
+    + CheckedAdd
+    + CheckedSub
+    + CheckedMul
+    + CheckedDiv
+    + ToPrimitive
+    + From<i64>
+    + Clone
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + FromStr<Err: std::error::Error + Send + Sync + 'static>
+{
+    fn xor(&self, other: &Self) -> Self;
+
+    /// The number of bits of the magnitude, 0 for zero.
+    fn bits(&self) -> u64;
+}
+
+impl Number for i64 {
+    fn xor(&self, other: &Self) -> Self {
+        self ^ other
+    }
+
+    fn bits(&self) -> u64 {
+        u64::from(64 - self.unsigned_abs().leading_zeros())
+    }
+}
+
+#[cfg(feature = "bigint")]
+impl Number for num_bigint::BigInt {
+    fn xor(&self, other: &Self) -> Self {
+        self ^ other
+    }
+
+    fn bits(&self) -> u64 {
+        num_bigint::BigInt::bits(self)
+    }
+}
+
+/// What the calibration results add up to, wide enough for any number of `i64` results.
+#[cfg(not(feature = "bigint"))]
+pub type Total = i128;
+
+/// What the calibration results add up to.
+#[cfg(feature = "bigint")]
+pub type Total = num_bigint::BigInt;
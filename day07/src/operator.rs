@@ -0,0 +1,307 @@
+use std::{fmt::Display, str::FromStr};
+
+use common::Error;
+use strum::VariantArray;
+use strum_macros::{Display, EnumString, VariantArray};
+
+use crate::Number;
+
+/// What [`BinaryOperator::invert`] knows about the left operand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inverse<N = i64> {
+    /// No left operand gives the target.
+    Impossible,
+    /// Only this one does.
+    Left(N),
+    /// Every left operand does, like anything times zero is zero.
+    Any,
+}
+
+/// An operator that can stand between two operands of an [`crate::Equation`].
+pub trait BinaryOperator<N: Number = i64>: Display {
+    /// `left op right`, `None` where it isn't defined or overflows.
+    fn apply(&self, left: &N, right: &N) -> Option<N>;
+
+    /// `left op right` for callers that only care about results no bigger than `limit`, `None`
+    /// for the others too. Operators whose results can get huge give up before computing them.
+    fn apply_up_to(&self, left: &N, right: &N, limit: &N) -> Option<N> {
+        self.apply(left, right).filter(|result| result.abs() <= limit.abs())
+    }
+
+    /// The left operand that gives `target` with `right`, so the solver can work backwards from
+    /// the result. Operators that can't tell return `None` and the solver evaluates the
+    /// operands in front of them forwards instead.
+    fn invert(&self, _target: &N, _right: &N) -> Option<Inverse<N>> {
+        None
+    }
+
+    /// Whether the result of non-negative operands is never negative. As long as this holds for
+    /// every operator, the solver drops negative targets.
+    fn keeps_non_negative(&self) -> bool {
+        true
+    }
+
+    /// How tightly the operator binds with [`crate::Semantics::Precedence`], higher first.
+    fn precedence(&self) -> u8 {
+        0
+    }
+
+    /// Whether the result of positive operands is never smaller than either of them, so a part
+    /// of an equation worth more than the result can't be part of a solution.
+    fn never_shrinks(&self) -> bool {
+        false
+    }
+}
+
+impl<N: Number, T: BinaryOperator<N> + ?Sized> BinaryOperator<N> for &T {
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        (**self).apply(left, right)
+    }
+
+    fn apply_up_to(&self, left: &N, right: &N, limit: &N) -> Option<N> {
+        (**self).apply_up_to(left, right, limit)
+    }
+
+    fn invert(&self, target: &N, right: &N) -> Option<Inverse<N>> {
+        (**self).invert(target, right)
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        (**self).keeps_non_negative()
+    }
+
+    fn precedence(&self) -> u8 {
+        (**self).precedence()
+    }
+
+    fn never_shrinks(&self) -> bool {
+        (**self).never_shrinks()
+    }
+}
+
+/// The built-in operators, named as on the command line. With precedence `**` binds tightest,
+/// then `*` and `/`, then `+` and `-`, then all others.
+#[derive(Debug, VariantArray, EnumString, Display, PartialEq, Eq, Clone, Copy)]
+pub enum Operator {
+    #[strum(to_string = "+")]
+    Add,
+    #[strum(to_string = "*")]
+    Mul,
+    #[strum(to_string = "||")]
+    Concat,
+    #[strum(to_string = "-")]
+    Sub,
+    /// Integer division, rounding towards zero.
+    #[strum(to_string = "/")]
+    Div,
+    #[strum(to_string = "**")]
+    Pow,
+    #[strum(to_string = "^")]
+    Xor,
+    #[strum(to_string = "min")]
+    Min,
+    #[strum(to_string = "max")]
+    Max,
+    /// Concatenation of the binary digits.
+    #[strum(to_string = "||2")]
+    Concat2,
+    /// Concatenation of the octal digits.
+    #[strum(to_string = "||8")]
+    Concat8,
+    /// Concatenation of the hexadecimal digits.
+    #[strum(to_string = "||16")]
+    Concat16,
+}
+
+impl Operator {
+    fn concat_base(&self) -> Option<i64> {
+        match self {
+            Operator::Concat => Some(10),
+            Operator::Concat2 => Some(2),
+            Operator::Concat8 => Some(8),
+            Operator::Concat16 => Some(16),
+            _ => None,
+        }
+    }
+}
+
+/// The factor that shifts a number left by as many digits as `operand` has in `base`, counted
+/// without going through a string.
+fn shift<N: Number>(operand: &N, base: i64) -> Option<N> {
+    let base = N::from(base);
+    let mut shift = base.clone();
+    while shift <= *operand {
+        shift = shift.checked_mul(&base)?;
+    }
+    Some(shift)
+}
+
+/// The biggest exponent of `**` for bases other than 0, 1 and -1. `i64` overflows long before,
+/// this keeps a `BigInt` power from growing without bounds.
+const MAX_EXPONENT: u32 = 4096;
+
+impl<N: Number> BinaryOperator<N> for Operator {
+    fn apply(&self, left: &N, right: &N) -> Option<N> {
+        if let Some(base) = self.concat_base() {
+            if left.is_negative() || right.is_negative() {
+                return None;
+            }
+            return left.checked_mul(&shift(right, base)?)?.checked_add(right);
+        }
+        match self {
+            Operator::Add => left.checked_add(right),
+            Operator::Mul => left.checked_mul(right),
+            Operator::Sub => left.checked_sub(right),
+            Operator::Div => left.checked_div(right),
+            Operator::Pow => {
+                let exponent = right.to_u32()?;
+                if exponent > MAX_EXPONENT && left.abs() > N::one() {
+                    return None;
+                }
+                num_traits::checked_pow(left.clone(), exponent as usize)
+            }
+            Operator::Xor => Some(left.xor(right)),
+            Operator::Min => Some(left.min(right).clone()),
+            Operator::Max => Some(left.max(right).clone()),
+            Operator::Concat | Operator::Concat2 | Operator::Concat8 | Operator::Concat16 => unreachable!(),
+        }
+    }
+
+    fn apply_up_to(&self, left: &N, right: &N, limit: &N) -> Option<N> {
+        // A base with `bits` bits is at least 2 ** (bits - 1), so is its power with that many
+        // times the exponent more.
+        let too_big = |exponent: u64| left.bits().saturating_sub(1).saturating_mul(exponent) >= limit.bits();
+        if *self == Operator::Pow && left.abs() > N::one() && right.to_u64().is_some_and(too_big) {
+            return None;
+        }
+        self.apply(left, right).filter(|result| result.abs() <= limit.abs())
+    }
+
+    fn invert(&self, target: &N, right: &N) -> Option<Inverse<N>> {
+        let left = |left: Option<N>| Some(left.map_or(Inverse::Impossible, Inverse::Left));
+        // The left operand times `by` is `dividend`, if it divides evenly.
+        let divide = |dividend: &N, by: &N| dividend.checked_div(by).filter(|quotient| quotient.checked_mul(by).as_ref() == Some(dividend));
+        if let Some(base) = self.concat_base() {
+            let Some(shift) = shift(right, base).filter(|_| !right.is_negative() && target >= right) else {
+                return Some(Inverse::Impossible);
+            };
+            return left(divide(&(target.clone() - right.clone()), &shift));
+        }
+        match self {
+            Operator::Add => left(target.checked_sub(right)),
+            Operator::Sub => left(target.checked_add(right)),
+            Operator::Mul if right.is_zero() => Some(if target.is_zero() { Inverse::Any } else { Inverse::Impossible }),
+            Operator::Mul => left(divide(target, right)),
+            Operator::Xor => left(Some(target.xor(right))),
+            // Many left operands give the same quotient, power or minimum.
+            _ => None,
+        }
+    }
+
+    fn keeps_non_negative(&self) -> bool {
+        *self != Operator::Sub
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Operator::Pow => 3,
+            Operator::Mul | Operator::Div => 2,
+            Operator::Add | Operator::Sub => 1,
+            _ => 0,
+        }
+    }
+
+    fn never_shrinks(&self) -> bool {
+        matches!(self, Operator::Add | Operator::Mul | Operator::Max) || self.concat_base().is_some()
+    }
+}
+
+/// Operators from their names, comma separated, e.g. `+,*,||,^`.
+pub fn parse_operators(s: &str) -> Result<Vec<Operator>, Error> {
+    s.split(',')
+        .map(|name| {
+            Operator::from_str(name.trim()).map_err(|_| {
+                let names: Vec<String> = Operator::VARIANTS.iter().map(|operator| operator.to_string()).collect();
+                Error::syntax(s, name, format!("unknown operator, expected one of {}", names.join(" ")))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(Operator::Concat.apply(&12, &345), Some(12345));
+        assert_eq!(Operator::Concat.apply(&12, &0), Some(120));
+        assert_eq!(Operator::Concat2.apply(&0b101, &0b11), Some(0b10111));
+        assert_eq!(Operator::Concat16.apply(&0xab, &0xc), Some(0xabc));
+        assert_eq!(Operator::Sub.apply(&3, &5), Some(-2));
+        assert_eq!(Operator::Div.apply(&7, &2), Some(3));
+        assert_eq!(Operator::Div.apply(&7, &0), None);
+        assert_eq!(Operator::Pow.apply(&3, &4), Some(81));
+        assert_eq!(Operator::Pow.apply(&3, &-1), None);
+        assert_eq!(Operator::Xor.apply(&0b1100, &0b1010), Some(0b0110));
+        assert_eq!(Operator::Max.apply(&3, &9), Some(9));
+        assert_eq!(Operator::Mul.apply(&i64::MAX, &2), None);
+    }
+
+    #[test]
+    fn test_apply_up_to() {
+        assert_eq!(Operator::Pow.apply_up_to(&3, &4, &81), Some(81));
+        assert_eq!(Operator::Pow.apply_up_to(&3, &4, &80), None);
+        assert_eq!(Operator::Pow.apply_up_to(&2, &62, &i64::MAX), Some(1 << 62));
+        assert_eq!(Operator::Pow.apply_up_to(&9, &4_000_000_000, &1000), None);
+        assert_eq!(Operator::Pow.apply_up_to(&1, &4_000_000_000, &1), Some(1));
+        assert_eq!(Operator::Pow.apply_up_to(&-3, &3, &27), Some(-27));
+        assert_eq!(Operator::Add.apply_up_to(&12, &30, &41), None);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_huge_exponent() {
+        use num_bigint::BigInt;
+
+        let (nine, huge) = (BigInt::from(9), BigInt::from(4_000_000_000i64));
+        assert_eq!(Operator::Pow.apply(&nine, &huge), None);
+        assert_eq!(Operator::Pow.apply(&BigInt::from(1), &huge), Some(BigInt::from(1)));
+        assert_eq!(Operator::Pow.apply(&nine, &BigInt::from(30)).map(|power| power.bits()), Some(96));
+        let eq: crate::Equation<BigInt> = "9: 9 4000000000 1".parse().unwrap();
+        assert!(!eq.is_solvable(&[Operator::Pow, Operator::Mul]));
+        let eq: crate::Equation<BigInt> = "81: 3 4000000000 4".parse().unwrap();
+        assert_eq!(eq.first_solution(&[Operator::Min, Operator::Pow]), Some(vec![Operator::Min, Operator::Pow]));
+    }
+
+    #[test]
+    fn test_invert() {
+        for operator in Operator::VARIANTS {
+            for (left, right) in [(0, 0), (7, 3), (12, 45), (100, 1), (5, 16)] {
+                let Some(target) = operator.apply(&left, &right) else {
+                    continue;
+                };
+                match operator.invert(&target, &right) {
+                    Some(Inverse::Left(inverse)) => assert_eq!(inverse, left, "{} {} {}", left, operator, right),
+                    Some(Inverse::Impossible) => panic!("{} {} {} = {} has no inverse", left, operator, right, target),
+                    Some(Inverse::Any) | None => {}
+                }
+            }
+        }
+        assert_eq!(Operator::Add.invert(&29, &19), Some(Inverse::Left(10)));
+        assert_eq!(Operator::Mul.invert(&191, &19), Some(Inverse::Impossible));
+        assert_eq!(Operator::Concat.invert(&1019, &9), Some(Inverse::Left(101)));
+        assert_eq!(Operator::Concat.invert(&1019, &18), Some(Inverse::Impossible));
+        assert_eq!(Operator::Concat.invert(&19, &19), Some(Inverse::Left(0)));
+        assert_eq!(Operator::Mul.invert(&0, &0), Some(Inverse::Any));
+        assert_eq!(Operator::Min.invert(&3, &9), None);
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        assert_eq!(parse_operators("+,*,||,^").unwrap(), [Operator::Add, Operator::Mul, Operator::Concat, Operator::Xor]);
+        assert_eq!(parse_operators("min, ||16").unwrap(), [Operator::Min, Operator::Concat16]);
+        let err = parse_operators("+,%").unwrap_err();
+        assert_eq!(err.to_string(), "<input>: unknown operator, expected one of + * || - / ** ^ min max ||2 ||8 ||16 ('%')");
+    }
+}
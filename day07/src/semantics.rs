@@ -0,0 +1,457 @@
+use std::{
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    str::FromStr,
+};
+
+use common::Error;
+use strum::VariantArray;
+use strum_macros::{Display, EnumString, VariantArray};
+
+use crate::{BinaryOperator, Equation, Inverse, Number};
+
+/// How the operators of an equation are evaluated.
+#[derive(Debug, VariantArray, EnumString, Display, PartialEq, Eq, Clone, Copy)]
+pub enum Semantics {
+    /// Strictly left to right, as in the puzzle.
+    #[strum(to_string = "left-to-right")]
+    LeftToRight,
+    /// Operators with a higher [`BinaryOperator::precedence`] first, then left to right.
+    #[strum(to_string = "precedence")]
+    Precedence,
+    /// In any order, as if parentheses could be put anywhere. Every run of operands can take
+    /// many values, so this gets slow for long equations, more so with `||`.
+    #[strum(to_string = "parenthesised")]
+    Parenthesised,
+}
+
+/// Semantics from their names, comma separated, e.g. `left-to-right,precedence`.
+pub fn parse_semantics(s: &str) -> Result<Vec<Semantics>, Error> {
+    s.split(',')
+        .map(|name| {
+            Semantics::from_str(name.trim()).map_err(|_| {
+                let names: Vec<String> = Semantics::VARIANTS.iter().map(|semantics| semantics.to_string()).collect();
+                Error::syntax(s, name, format!("unknown semantics, expected one of {}", names.join(" ")))
+            })
+        })
+        .collect()
+}
+
+/// A way to solve an equation and the semantics it needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solved<O> {
+    pub semantics: Semantics,
+    /// One between each two operands.
+    pub operators: Vec<O>,
+    /// The equation with the operators and the parentheses it needs, e.g. `81 + (40 * 27) = 1161`.
+    pub expression: String,
+}
+
+/// Whether a part of the equation worth more than the result can be dropped: true if no
+/// operator shrinks and no operand is zero or negative.
+fn can_prune<N: Number, O: BinaryOperator<N>>(equation: &Equation<N>, operators: &[O]) -> bool {
+    operators.iter().all(|operator| operator.never_shrinks()) && equation.operands.iter().all(|operand| operand.is_positive())
+}
+
+/// An expression evaluated by precedence as far as its operators allow: the values and the
+/// operators that wait for an operand to their right, as that may bind tighter.
+#[derive(Clone)]
+struct Pending<'a, N, O> {
+    values: Vec<N>,
+    operators: Vec<&'a O>,
+}
+
+impl<'a, N: Number, O: BinaryOperator<N>> Pending<'a, N, O> {
+    fn new(first: &N) -> Self {
+        Pending {
+            values: vec![first.clone()],
+            operators: Vec::new(),
+        }
+    }
+
+    /// Applies the waiting operators that bind at least as tightly as `precedence`, `None` if one
+    /// of them isn't defined for its operands or, with a `limit`, its result is beyond it.
+    fn reduce(&mut self, precedence: u8, limit: Option<&N>) -> Option<()> {
+        while let Some(operator) = self.operators.pop_if(|operator| operator.precedence() >= precedence) {
+            let right = self.values.pop()?;
+            let left = self.values.pop()?;
+            let value = match limit {
+                Some(limit) => operator.apply_up_to(&left, &right, limit),
+                None => operator.apply(&left, &right),
+            };
+            self.values.push(value?);
+        }
+        Some(())
+    }
+
+    fn push(&mut self, operator: &'a O, operand: &N, limit: Option<&N>) -> Option<()> {
+        self.reduce(operator.precedence(), limit)?;
+        self.operators.push(operator);
+        self.values.push(operand.clone());
+        Some(())
+    }
+
+    fn finish(mut self, limit: Option<&N>) -> Option<N> {
+        self.reduce(0, limit)?;
+        self.values.pop()
+    }
+}
+
+/// Finds operators that solve the equation evaluated by precedence, working backwards like the
+/// left to right solver: the operator at the top of the expression is the last one of the
+/// lowest precedence in it, with the operands after it all bound tighter. So for each place and
+/// precedence of that operator, every value of the run on its right is undone from the result,
+/// and the operands on its left have to give what's left with operators of that precedence or
+/// higher.
+pub(crate) fn first_with_precedence<N: Number, O: BinaryOperator<N> + Clone>(equation: &Equation<N>, operators: &[O]) -> Option<Vec<O>> {
+    let mut precedences: Vec<u8> = operators.iter().map(|operator| operator.precedence()).collect();
+    precedences.sort();
+    precedences.dedup();
+    let mut search = PrecedenceSearch {
+        equation,
+        operators,
+        precedences,
+        limit: can_prune(equation, operators).then_some(&equation.expected_result),
+        non_negative: operators.iter().all(|operator| operator.keeps_non_negative()),
+        runs: HashMap::new(),
+        failed: HashSet::new(),
+    };
+    let last = equation.operands.len() - 1;
+    let mut chosen = vec![None; last];
+    search.backward(last, 0, &equation.expected_result, &mut chosen).then_some(())?;
+    chosen.into_iter().collect()
+}
+
+/// The values a run of operands takes with operators of some precedences, each with the first
+/// choice of operators found for it.
+type Evaluations<N, O> = Vec<(N, Vec<O>)>;
+
+struct PrecedenceSearch<'a, N, O> {
+    equation: &'a Equation<N>,
+    operators: &'a [O],
+    /// The precedences of the operators, lowest first.
+    precedences: Vec<u8>,
+    /// What no part of the expression can be worth more than, where [`can_prune`] holds.
+    limit: Option<&'a N>,
+    /// No operator makes a negative number out of the (never negative) operands.
+    non_negative: bool,
+    /// The evaluations of the runs right of the top operator, by first and last operand and the
+    /// top operator's precedence.
+    runs: HashMap<(usize, usize, u8), Rc<Evaluations<N, O>>>,
+    /// The calls of [`PrecedenceSearch::backward`] that found nothing.
+    failed: HashSet<(usize, u8, N)>,
+}
+
+impl<'a, N: Number, O: BinaryOperator<N> + Clone> PrecedenceSearch<'a, N, O> {
+    /// Whether the operands up to `last` give `target` with operators of precedence `lowest` or
+    /// higher, the operators that do in `chosen`.
+    fn backward(&mut self, last: usize, lowest: u8, target: &N, chosen: &mut [Option<O>]) -> bool {
+        if last == 0 {
+            return self.equation.operands[0] == *target;
+        }
+        let overshot = self.limit.is_some_and(|limit| target > limit) || (self.non_negative && target.is_negative());
+        if overshot || self.failed.contains(&(last, lowest, target.clone())) {
+            return false;
+        }
+        let precedences: Vec<u8> = self.precedences.iter().copied().filter(|precedence| *precedence >= lowest).collect();
+        for precedence in precedences {
+            for top in (0..last).rev() {
+                let rights = self.run(top + 1, last, precedence);
+                for operator in self.operators.iter().filter(|operator| operator.precedence() == precedence) {
+                    for (right, right_operators) in rights.iter() {
+                        let left = match operator.invert(target, right) {
+                            Some(Inverse::Impossible) => continue,
+                            Some(Inverse::Left(left)) => left,
+                            Some(Inverse::Any) => match self.evaluate(0, top, &|operator| operator.precedence() >= precedence).into_iter().next() {
+                                Some((_, left_operators)) => {
+                                    fill(chosen, 0, &left_operators);
+                                    return finish(chosen, top, operator, right_operators);
+                                }
+                                None => continue,
+                            },
+                            None => {
+                                // No way back through this operator, try the operands on its left forwards.
+                                let lefts = self.evaluate(0, top, &|operator| operator.precedence() >= precedence);
+                                let fits = |left: &N| self.apply(operator, left, right).as_ref() == Some(target);
+                                match lefts.into_iter().find(|(left, _)| fits(left)) {
+                                    Some((_, left_operators)) => {
+                                        fill(chosen, 0, &left_operators);
+                                        return finish(chosen, top, operator, right_operators);
+                                    }
+                                    None => continue,
+                                }
+                            }
+                        };
+                        if self.backward(top, precedence, &left, chosen) {
+                            return finish(chosen, top, operator, right_operators);
+                        }
+                    }
+                }
+            }
+        }
+        self.failed.insert((last, lowest, target.clone()));
+        false
+    }
+
+    /// The values of the operands `first..=last` with operators that bind tighter than
+    /// `precedence`.
+    fn run(&mut self, first: usize, last: usize, precedence: u8) -> Rc<Evaluations<N, O>> {
+        if let Some(evaluations) = self.runs.get(&(first, last, precedence)) {
+            return evaluations.clone();
+        }
+        let evaluations = Rc::new(self.evaluate(first, last, &|operator| operator.precedence() > precedence));
+        self.runs.insert((first, last, precedence), evaluations.clone());
+        evaluations
+    }
+
+    /// Every value of the operands `first..=last` with the operators `allowed` picks, by
+    /// precedence.
+    fn evaluate(&self, first: usize, last: usize, allowed: &dyn Fn(&O) -> bool) -> Evaluations<N, O> {
+        fn forward<'a, N: Number, O: BinaryOperator<N> + Clone>(
+            search: &PrecedenceSearch<'a, N, O>,
+            operands: &[N],
+            allowed: &dyn Fn(&O) -> bool,
+            pending: Pending<'a, N, O>,
+            chosen: &mut Vec<O>,
+            evaluations: &mut HashMap<N, Vec<O>>,
+        ) {
+            let Some((operand, rest)) = operands.split_first() else {
+                if let Some(value) = pending.finish(search.limit) {
+                    evaluations.entry(value).or_insert_with(|| chosen.clone());
+                }
+                return;
+            };
+            for operator in search.operators.iter().filter(|operator| allowed(operator)) {
+                let mut next = pending.clone();
+                if next.push(operator, operand, search.limit).is_some() {
+                    chosen.push(operator.clone());
+                    forward(search, rest, allowed, next, chosen, evaluations);
+                    chosen.pop();
+                }
+            }
+        }
+        let operands = &self.equation.operands[first..=last];
+        let mut evaluations = HashMap::new();
+        forward(self, &operands[1..], allowed, Pending::new(&operands[0]), &mut Vec::new(), &mut evaluations);
+        let mut evaluations: Evaluations<N, O> = evaluations.into_iter().collect();
+        evaluations.sort_by(|a, b| a.0.cmp(&b.0));
+        evaluations
+    }
+
+    fn apply(&self, operator: &O, left: &N, right: &N) -> Option<N> {
+        match self.limit {
+            Some(limit) => operator.apply_up_to(left, right, limit),
+            None => operator.apply(left, right),
+        }
+    }
+}
+
+/// Puts `operators` into `chosen` from `first` on.
+fn fill<O: Clone>(chosen: &mut [Option<O>], first: usize, operators: &[O]) {
+    for (slot, operator) in chosen[first..].iter_mut().zip(operators) {
+        *slot = Some(operator.clone());
+    }
+}
+
+/// Records `operator` after operand `top` and the run right of it, for a solution found.
+fn finish<O: Clone>(chosen: &mut [Option<O>], top: usize, operator: &O, right: &[O]) -> bool {
+    chosen[top] = Some(operator.clone());
+    fill(chosen, top + 1, right);
+    true
+}
+
+/// The values each run of operands can take, `values[first][last]` for the run that starts at
+/// `first` and ends at `last`.
+type Values<N> = Vec<Vec<HashSet<N>>>;
+
+/// Finds a parenthesisation with interval DP: the values every run of operands can take, from
+/// the values of the two shorter runs on either side of each operator in it. The whole equation
+/// only has to reach the result, which [`derive`] checks without the values of the whole run.
+pub(crate) fn first_parenthesised<N: Number, O: BinaryOperator<N> + Clone>(equation: &Equation<N>, operators: &[O]) -> Option<(Vec<O>, String)> {
+    let n = equation.operands.len();
+    let target = &equation.expected_result;
+    let prune = can_prune(equation, operators);
+    let mut values: Values<N> = vec![vec![HashSet::new(); n]; n];
+    for (i, operand) in equation.operands.iter().enumerate() {
+        values[i][i].insert(operand.clone());
+    }
+    for len in 2..n {
+        for first in 0..=n - len {
+            let last = first + len - 1;
+            let mut run = HashSet::new();
+            for split in first..last {
+                for left in values[first][split].iter() {
+                    for right in values[split + 1][last].iter() {
+                        let results = operators.iter().filter_map(|operator| {
+                            if prune {
+                                operator.apply_up_to(left, right, target)
+                            } else {
+                                operator.apply(left, right)
+                            }
+                        });
+                        run.extend(results);
+                    }
+                }
+            }
+            values[first][last] = run;
+        }
+    }
+
+    let mut chosen: Vec<Option<O>> = vec![None; n - 1];
+    let expression = rebuild(&values, equation, operators, 0, n - 1, target.clone(), &mut chosen)?;
+    let chosen = chosen.into_iter().collect::<Option<Vec<O>>>()?;
+    Some((chosen, format!("{} = {}", expression, target)))
+}
+
+/// How the run of operands `first..=last` gives `target`: the first split, operator and smallest
+/// values on either side that do. The value on the left is looked up through the operator's
+/// inverse where it has one.
+fn derive<N: Number, O: BinaryOperator<N>>(values: &Values<N>, operators: &[O], first: usize, last: usize, target: &N) -> Option<(usize, usize, N, N)> {
+    for split in first..last {
+        let lefts = &values[first][split];
+        let mut rights: Vec<&N> = values[split + 1][last].iter().collect();
+        rights.sort();
+        for (i, operator) in operators.iter().enumerate() {
+            for &right in rights.iter() {
+                let left = match operator.invert(target, right) {
+                    Some(Inverse::Impossible) => None,
+                    Some(Inverse::Left(left)) => lefts.contains(&left).then_some(left),
+                    Some(Inverse::Any) => lefts.iter().min().cloned(),
+                    None => lefts.iter().filter(|left| operator.apply_up_to(left, right, target).as_ref() == Some(target)).min().cloned(),
+                };
+                if let Some(left) = left {
+                    return Some((split, i, left, right.clone()));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// The expression that gives `target` for the operands `first..=last`, with parentheses around
+/// every run of more than one operand inside it.
+fn rebuild<N: Number, O: BinaryOperator<N> + Clone>(
+    values: &Values<N>,
+    equation: &Equation<N>,
+    operators: &[O],
+    first: usize,
+    last: usize,
+    target: N,
+    chosen: &mut [Option<O>],
+) -> Option<String> {
+    if first == last {
+        return (equation.operands[first] == target).then(|| target.to_string());
+    }
+    let (split, operator, left, right) = derive(values, operators, first, last, &target)?;
+    chosen[split] = Some(operators[operator].clone());
+    let wrap = |expression: String, first: usize, last: usize| if first < last { format!("({})", expression) } else { expression };
+    let left = wrap(rebuild(values, equation, operators, first, split, left, chosen)?, first, split);
+    let right = wrap(rebuild(values, equation, operators, split + 1, last, right, chosen)?, split + 1, last);
+    Some(format!("{} {} {}", left, operators[operator], right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Day07, Operator};
+    use common::Solution;
+
+    /// Evaluates the operands with operators of higher precedence first, `None` if an operator
+    /// isn't defined for its operands.
+    fn evaluate_with_precedence<N: Number, O: BinaryOperator<N>>(operands: &[N], operators: &[O]) -> Option<N> {
+        let mut pending = Pending::new(&operands[0]);
+        for (operator, operand) in operators.iter().zip(&operands[1..]) {
+            pending.push(operator, operand, None)?;
+        }
+        pending.finish(None)
+    }
+
+    /// Whether any choice of operators solves the equation by precedence, trying them all.
+    fn brute_force_solvable(eq: &Equation, operators: &[Operator]) -> bool {
+        let slots = eq.operands.len() - 1;
+        (0..operators.len().pow(slots as u32)).any(|mut combination| {
+            let chosen: Vec<Operator> = (0..slots)
+                .map(|_| {
+                    let operator = operators[combination % operators.len()];
+                    combination /= operators.len();
+                    operator
+                })
+                .collect();
+            evaluate_with_precedence(&eq.operands, &chosen) == Some(eq.expected_result)
+        })
+    }
+
+    #[test]
+    fn test_precedence() {
+        use Operator::*;
+        assert_eq!(evaluate_with_precedence::<i64, _>(&[81, 40, 27], &[Add, Mul]), Some(81 + 40 * 27));
+        assert_eq!(evaluate_with_precedence::<i64, _>(&[2, 3, 4, 5], &[Mul, Add, Mul]), Some(26));
+        assert_eq!(evaluate_with_precedence::<i64, _>(&[2, 3, 2, 1], &[Mul, Pow, Sub]), Some(17));
+        assert_eq!(evaluate_with_precedence::<i64, _>(&[10, 4, 3], &[Sub, Sub]), Some(3));
+        assert_eq!(evaluate_with_precedence::<i64, _>(&[1, 2, 3], &[Concat, Mul]), Some(16));
+        assert_eq!(evaluate_with_precedence::<i64, _>(&[1, 0], &[Div]), None);
+    }
+
+    #[test]
+    fn test_precedence_matches_brute_force() {
+        use Operator::*;
+
+        let data = Day07::INPUTS.read("testdata").unwrap();
+        let mut equations = Day07.parse(&data).unwrap().small;
+        equations.retain(|eq| eq.operands.len() <= 6);
+        let data = Day07::INPUTS.read("example").unwrap();
+        equations.extend(Day07.parse(&data).unwrap().small);
+        let extra = ["6: 3 9 2 4", "0: 5 0 0", "0: 3 4 0 7", "1: 12 3 4 8", "7: 2 3 1", "17: 2 3 2 1", "5: 2 3 2 1"];
+        equations.extend(extra.map(|line| Equation::<i64>::from_str(line).unwrap()));
+        let operator_sets = [
+            &[Add, Mul, Concat][..],
+            &[Add, Sub, Mul, Div],
+            &[Add, Mul, Pow, Sub],
+            &[Xor, Min, Max, Pow, Concat2],
+            &[Sub, Concat16, Mul, Max],
+        ];
+        for eq in equations.iter() {
+            for operators in operator_sets {
+                let solution = first_with_precedence(eq, operators);
+                assert_eq!(solution.is_some(), brute_force_solvable(eq, operators), "{:?} with {:?}", eq, operators);
+                if let Some(solution) = solution {
+                    let value = evaluate_with_precedence(&eq.operands, &solution);
+                    assert_eq!(value, Some(eq.expected_result), "{}", eq.expression(&solution));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parenthesised() {
+        let operators = [Operator::Add, Operator::Mul];
+        let eq = Equation::<i64>::from_str("1161: 81 40 27").unwrap();
+        let (chosen, expression) = first_parenthesised(&eq, &operators).unwrap();
+        assert_eq!(chosen, [Operator::Add, Operator::Mul]);
+        assert_eq!(expression, "81 + (40 * 27) = 1161");
+        let eq = Equation::<i64>::from_str("20: 2 3 4").unwrap();
+        assert_eq!(first_parenthesised(&eq, &operators).unwrap().1, "(2 + 3) * 4 = 20");
+        let eq = Equation::<i64>::from_str("31: 2 3 4").unwrap();
+        assert_eq!(first_parenthesised(&eq, &operators), None);
+    }
+
+    #[test]
+    fn test_solved_by() {
+        let operators = [Operator::Add, Operator::Mul];
+        let all = Semantics::VARIANTS;
+        let eq = Equation::<i64>::from_str("3267: 81 40 27").unwrap();
+        assert_eq!(eq.solved_by(&operators, all).unwrap().semantics, Semantics::LeftToRight);
+        let eq = Equation::<i64>::from_str("1161: 81 40 27").unwrap();
+        let solved = eq.solved_by(&operators, all).unwrap();
+        assert_eq!(solved.semantics, Semantics::Precedence);
+        assert_eq!(solved.expression, "81 + 40 * 27 = 1161");
+        let eq = Equation::<i64>::from_str("70: 2 3 4 5").unwrap();
+        let solved = eq.solved_by(&operators, all).unwrap();
+        assert_eq!(solved.semantics, Semantics::Parenthesised);
+        assert_eq!(solved.expression, "2 * ((3 + 4) * 5) = 70");
+        assert_eq!(eq.solved_by(&operators, &[Semantics::LeftToRight, Semantics::Precedence]), None);
+
+        assert_eq!(parse_semantics("precedence, parenthesised").unwrap(), [Semantics::Precedence, Semantics::Parenthesised]);
+        assert!(parse_semantics("right-to-left").is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use common::{
+    day_inputs,
+    geom::{Point, Rect, Vector},
+    grid::Grid,
+    inputs::DayInputs,
+    render::{Canvas, Color},
+    Error, Part, Solution,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+pub struct Day08;
+
+impl Solution for Day08 {
+    const DAY: u8 = 8;
+    const INPUTS: DayInputs = day_inputs!("example", "testdata");
+    type Parsed = CityMap;
+    type Answer = usize;
+
+    fn parse(&self, input: &str) -> Result<Self::Parsed, Error> {
+        let mut city_map = CityMap::from_str(input)?;
+        city_map.create_lines();
+        Ok(city_map)
+    }
+
+    fn part1(&self, city_map: &Self::Parsed) -> Self::Answer {
+        let mut city_map = city_map.clone();
+        city_map.create_antinodes();
+        city_map.unique_antinodes().len()
+    }
+
+    fn part2(&self, city_map: &Self::Parsed) -> Self::Answer {
+        let mut city_map = city_map.clone();
+        city_map.create_resonant_antinodes();
+        city_map.unique_antinodes().len()
+    }
+
+    fn canvas(&self, city_map: &Self::Parsed, part: Part) -> Option<Canvas> {
+        let mut city_map = city_map.clone();
+        match part {
+            Part::One => city_map.create_antinodes(),
+            Part::Two => city_map.create_resonant_antinodes(),
+        }
+        Some(city_map.get_canvas())
+    }
+}
+
+const EMPTY_SPOT: char = '.';
+const ANTINODE: char = '#';
+const ANTINODE_COLOR: Color = Color::BrightRed;
+
+#[derive(Debug, Default, Clone)]
+pub struct CityMap {
+    antennas: Vec<Antenna>,
+    ants_by_frequency: HashMap<char, Vec<Antenna>>,
+    lines_by_antennas: HashMap<Antenna, Vec<GeoLine>>,
+    antinodes_by_line: HashMap<GeoLine, Vec<Point>>,
+    dimensions: Rect,
+}
+
+impl CityMap {
+    fn new() -> Self {
+        CityMap::default()
+    }
+
+    fn add_antenna(&mut self, antenna: Antenna) {
+        self.antennas.push(antenna.clone());
+        self.ants_by_frequency.entry(antenna.frequency).or_default().push(antenna);
+    }
+
+    fn create_lines(&mut self) {
+        self.lines_by_antennas.clear();
+        for freq in self.ants_by_frequency.keys() {
+            let antennas = self.ants_by_frequency.get(freq).unwrap();
+            for i in 0..antennas.len().saturating_sub(1) {
+                let mut lines: Vec<GeoLine> = Vec::new();
+                for j in (i + 1)..antennas.len() {
+                    let geo_line = GeoLine::new(antennas[i].clone(), antennas[j].clone());
+                    lines.push(geo_line);
+                }
+                self.lines_by_antennas.insert(antennas[i].clone(), lines);
+            }
+        }
+    }
+
+    fn create_antinodes(&mut self) {
+        self.antinodes_by_line.clear();
+        for line in self.lines_by_antennas.values().flatten() {
+            let d = line.distance();
+            let antinodes = [line.a.location - d, line.a.location + d, line.b.location - d, line.b.location + d];
+            let antinodes: Vec<Point> = antinodes
+                .into_iter()
+                .filter(|node| *node != line.a.location && *node != line.b.location && self.dimensions.contains(node))
+                .collect();
+            self.antinodes_by_line.insert(line.clone(), antinodes);
+        }
+    }
+
+    /// Part 2: every location on the map in line with two antennas of the same frequency,
+    /// including the antennas themselves.
+    fn create_resonant_antinodes(&mut self) {
+        self.antinodes_by_line.clear();
+        for line in self.lines_by_antennas.values().flatten() {
+            let step = line.direction();
+            let mut antinodes: Vec<Point> = Vec::new();
+            for step in [step, -step] {
+                let mut node = line.a.location;
+                while self.dimensions.contains(&node) {
+                    antinodes.push(node);
+                    node += step;
+                }
+            }
+            self.antinodes_by_line.insert(line.clone(), antinodes);
+        }
+    }
+
+    fn unique_antinodes(&self) -> HashSet<&Point> {
+        self.antinodes_by_line.values().flatten().collect()
+    }
+
+    /// The map with the antinodes created last, antennas coloured by frequency and
+    /// a line through each pair of antennas out to its outermost antinodes.
+    pub fn get_canvas(&self) -> Canvas {
+        let mut grid = Grid::new(self.dimensions.rows() as usize, self.dimensions.cols() as usize, EMPTY_SPOT);
+        for antenna in self.antennas.iter() {
+            grid[antenna.location] = antenna.frequency;
+        }
+        let mut frequencies: Vec<char> = self.ants_by_frequency.keys().copied().collect();
+        frequencies.sort();
+        let color = |frequency: char| Color::nth(frequencies.iter().position(|f| *f == frequency).unwrap_or(0));
+
+        let mut canvas = Canvas::new(grid);
+        let mut lines: Vec<&GeoLine> = self.antinodes_by_line.keys().collect();
+        lines.sort_by_key(|line| (line.a.location, line.b.location));
+        for line in lines {
+            let on_line = self.antinodes_by_line[line].iter().chain([&line.a.location, &line.b.location]);
+            // Points on a line are in order along the line when sorted by row, then column.
+            let (Some(first), Some(last)) = (on_line.clone().min(), on_line.max()) else {
+                continue;
+            };
+            canvas.add_polyline([*first, *last], color(line.a.frequency));
+        }
+        let antinodes = self.unique_antinodes();
+        for antinode in antinodes.iter() {
+            canvas.mark_glyph(**antinode, ANTINODE_COLOR, ANTINODE);
+        }
+        for antenna in self.antennas.iter() {
+            canvas.mark(antenna.location, color(antenna.frequency));
+        }
+        canvas.add_legend(ANTINODE_COLOR, format!("{} {} antinodes", ANTINODE, antinodes.len()));
+        canvas
+    }
+}
+
+impl FromStr for CityMap {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let grid = Grid::from_str(s)?;
+        let mut city_map = CityMap::new();
+        city_map.dimensions = grid.bounds();
+        for ((row, col), spot) in grid.iter() {
+            if *spot != EMPTY_SPOT && *spot != ANTINODE {
+                city_map.add_antenna(Antenna::new(*spot, row as i64, col as i64));
+            }
+        }
+
+        Ok(city_map)
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+struct Antenna {
+    frequency: char,
+    location: Point,
+}
+
+impl Antenna {
+    fn new(frequency: char, row: i64, col: i64) -> Self {
+        Antenna {
+            frequency,
+            location: Point::new(row, col),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+struct GeoLine {
+    a: Antenna,
+    b: Antenna,
+}
+
+impl GeoLine {
+    fn new(a: Antenna, b: Antenna) -> Self {
+        Self { a, b }
+    }
+
+    fn distance(&self) -> Vector {
+        self.a.location - self.b.location
+    }
+
+    /// Smallest integer step along the line, so every grid location on it is reached.
+    fn direction(&self) -> Vector {
+        self.distance().reduced()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    // Note this useful idiom: importing names from outer (for mod tests) scope.
+    use super::*;
+    use common::render::RenderOptions;
+
+    #[test]
+    fn test_create_antinodes() {
+        let data = Day08::INPUTS.read("example").unwrap();
+        let mut city_map = CityMap::from_str(&data).unwrap();
+        city_map.create_lines();
+        city_map.create_antinodes();
+        assert_eq!(city_map.unique_antinodes().len(), 14);
+        city_map.create_resonant_antinodes();
+        assert_eq!(city_map.unique_antinodes().len(), 34);
+    }
+
+    #[test]
+    fn test_canvas() {
+        let city_map = Day08.parse(&Day08::INPUTS.read("example").unwrap()).unwrap();
+        let canvas = Solution::canvas(&Day08, &city_map, Part::One).unwrap();
+        let out = canvas.render(&RenderOptions::default());
+        assert_eq!(out.lines().next(), Some("......#....#"));
+        assert!(out.ends_with("bright red: # 14 antinodes\n"));
+        assert_eq!(canvas.polylines().len(), 9);
+        assert_eq!(canvas.polylines()[0].points, vec![Point::new(0, 11), Point::new(3, 2)]);
+    }
+
+    #[test]
+    fn test_lines() {
+        let data = Day08::INPUTS.read("example").unwrap();
+        let mut city_map = CityMap::from_str(&data).unwrap();
+        city_map.create_lines();
+        // println!("{:#?}", city_map.lines_by_antennas);
+        let line_a = city_map.lines_by_antennas.get(&Antenna::new('0', 3, 7)).unwrap();
+        assert_eq!(line_a.len(), 1);
+        assert_eq!(line_a[0].b, Antenna::new('0', 4, 4));
+        let line_a = city_map.lines_by_antennas.get(&Antenna::new('0', 1, 8)).unwrap();
+        assert_eq!(line_a.len(), 3);
+        assert_eq!(line_a[1].b, Antenna::new('0', 3, 7));
+        assert_eq!(line_a[1].direction(), Vector::new(-2, 1));
+        let line_a = city_map.lines_by_antennas.get(&Antenna::new('0', 2, 5)).unwrap();
+        assert_eq!(line_a.len(), 2);
+        assert_eq!(line_a[0].b, Antenna::new('0', 3, 7));
+        assert_eq!(line_a[0].direction(), Vector::new(-1, -2));
+        // assert_eq!(city_map.lines_by_antennas.len(), 10);
+    }
+
+    #[test]
+    fn test_ragged_city_map() {
+        let err = CityMap::from_str("..a.\n...\n").unwrap_err();
+        assert_eq!(err.to_string(), "<input>:2:1: expected 4 columns like the rows before, found 3 ('...')");
+    }
+
+    #[test]
+    fn test_load_city_map() {
+        let data = Day08::INPUTS.read("example").unwrap();
+        let city_map = CityMap::from_str(&data).unwrap();
+        assert_eq!(city_map.antennas.len(), 7);
+        assert_eq!(city_map.ants_by_frequency.len(), 2);
+        assert_eq!(city_map.ants_by_frequency.get(&'A').unwrap().len(), 3);
+        assert_eq!(city_map.ants_by_frequency.get(&'0').unwrap().len(), 4);
+    }
+}